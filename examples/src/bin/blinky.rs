@@ -1,28 +1,52 @@
 #![no_main]
 #![no_std]
-#![feature(type_alias_impl_trait)]
 
 use async_hal::{
+    binding::On,
     delay::{DelayMs, Timer},
-    executor::{Executor, NonPending},
+    executor::{Arena, Level, Nvic, StaticExecutor},
 };
 use async_hal_examples as _;
-use core::future::Future;
 use cortex_m::peripheral::NVIC;
 use cortex_m_rt::entry;
 use defmt::println;
 use stm32f1xx_hal::{
-    pac::{self, interrupt, Peripherals},
+    gpio::{Output, PushPull, PC13},
+    pac::{self, interrupt, Peripherals, TIM2},
     prelude::*,
-    timer::Event,
+    timer::{CounterMs, Event},
 };
 
-type App = impl Future<Output = ()>;
-static mut EXECUTOR: Executor<NonPending, App> = Executor::non_pending();
+// Waking the task pends the otherwise unused EXTI0 so the executor is polled again.
+// It preempts TIM2, so the task clears the update flag before the TIM2 handler returns
+static EXECUTOR: StaticExecutor<Arena<Level<Nvic<pac::Interrupt>>, 1>> =
+    StaticExecutor::arena(Level::new(Nvic::new(pac::Interrupt::EXTI0), 2));
+
+// The timer is woken by the TIM2 handler, whose vector is marked by the TIM2 peripheral type
+type Counter = On<TIM2, CounterMs<TIM2>>;
+
+async_hal::bind_interrupts! {
+    struct Irqs {
+        TIM2 => Counter;
+    }
+}
+
+async_hal::static_task! {
+    // Create an async task to blink the LED
+    async fn blink(led: PC13<Output<PushPull>>, timer: Timer<Counter>) {
+        loop {
+            println!("Blink!");
+
+            led.toggle();
+
+            timer.delay_ms(1_000).await.unwrap();
+        }
+    }
+}
 
 #[interrupt]
-fn TIM2() {
-    _ = unsafe { EXECUTOR.poll() };
+fn EXTI0() {
+    _ = EXECUTOR.poll();
 }
 
 #[entry]
@@ -33,7 +57,7 @@ fn main() -> ! {
 
     // Configure PC13 pin to blink LED
     let mut gpioc = dp.GPIOC.split();
-    let mut led = gpioc.pc13.into_push_pull_output(&mut gpioc.crh);
+    let led = gpioc.pc13.into_push_pull_output(&mut gpioc.crh);
 
     // Init clocks
     let rcc = dp.RCC.constrain();
@@ -47,27 +71,21 @@ fn main() -> ! {
     // Create a counter using TIM2
     let mut counter = dp.TIM2.counter_ms(&clocks);
     counter.listen(Event::Update);
-    let mut timer = Timer::new(counter);
-
-    // Create an async task to blink the LED
-    let task = async move {
-        loop {
-            println!("Blink!");
+    let timer = Timer::bind(On::new(counter), Irqs);
 
-            led.toggle();
-
-            timer.delay_ms(1_000).await.unwrap();
-        }
-    };
+    // Safety: no critical sections are based on these priorities
+    unsafe {
+        EXECUTOR.interrupt().configure();
+        Level::new(Nvic::<pac::Interrupt>::new(pac::Interrupt::TIM2), 1).configure();
+    }
 
     // Spawn the task on the executor
-    _ = unsafe {
-        _ = EXECUTOR.spawn(task);
-        EXECUTOR.poll()
-    };
+    _ = EXECUTOR.spawn(blink(led, timer).unwrap());
+    _ = EXECUTOR.poll();
 
-    // Enable TIM2 interrupt
+    // Enable the executor and TIM2 interrupts
     unsafe {
+        NVIC::unmask(pac::Interrupt::EXTI0);
         NVIC::unmask(pac::Interrupt::TIM2);
     }
 
@@ -1,30 +1,28 @@
 #![no_main]
 #![no_std]
-#![feature(type_alias_impl_trait)]
 
 use async_hal::{
-    executor::{Executor, NonPending},
-    io::AsyncRead,
+    io::{self, AsyncRead},
     serial::{Reader, SerialRead},
 };
 use async_hal_examples as _;
-use core::future::Future;
-use cortex_m::peripheral::NVIC;
 use cortex_m_rt::entry;
 use defmt::println;
 use stm32f1xx_hal::{
-    pac::{self, interrupt, Peripherals},
+    pac::{self, Peripherals, USART3},
     prelude::*,
-    serial::{Config, Serial},
+    serial::{Config, Rx, Serial},
 };
 
-type App = impl Future<Output = ()>;
-static mut EXECUTOR: Executor<NonPending, App> = Executor::non_pending();
+// Create an async task to read serial data, polled by the USART3 interrupt
+#[async_hal::task(interrupt = pac::Interrupt::USART3)]
+async fn echo(mut reader: io::Reader<Reader<Rx<USART3>, u8>>) {
+    loop {
+        let mut buf = [0; 1];
+        reader.read(&mut buf).await.unwrap();
 
-#[interrupt]
-fn USART3() {
-    println!("USART3");
-    _ = unsafe { EXECUTOR.poll() };
+        println!("Received: {}", &buf);
+    }
 }
 
 #[entry]
@@ -55,28 +53,10 @@ fn main() -> ! {
     rx.listen();
 
     // Create async serial reader
-    let mut reader = Reader::new(rx).into_reader();
-
-    // Create an async task to read serial data
-    let task = async move {
-        loop {
-            let mut buf = [0; 1];
-            reader.read(&mut buf).await.unwrap();
+    let reader = Reader::new(rx).into_reader();
 
-            println!("Received: {}", &buf);
-        }
-    };
-
-    // Spawn the task on the executor
-    _ = unsafe {
-        _ = EXECUTOR.spawn(task);
-        EXECUTOR.poll()
-    };
-
-    // Enable TIM2 interrupt
-    unsafe {
-        NVIC::unmask(pac::Interrupt::USART3);
-    }
+    // Spawn the task and enable the USART3 interrupt
+    echo(reader).unwrap();
 
     // Run in low-power mode
     loop {
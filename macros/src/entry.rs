@@ -0,0 +1,110 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Error, ItemFn, ReturnType, Type};
+
+/// Expand `#[main]` into a Cortex-M entry point that runs the future with `block_on`,
+/// sleeping with `wfi` while it's pending.
+pub fn expand(args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    if !args.is_empty() {
+        return Err(Error::new_spanned(args, "`main` doesn't take arguments"));
+    }
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = syn::parse2(item)?;
+
+    if sig.asyncness.is_none() {
+        return Err(Error::new_spanned(
+            sig.fn_token,
+            "`main` must be `async fn`",
+        ));
+    }
+    if !sig.inputs.is_empty() {
+        return Err(Error::new_spanned(
+            &sig.inputs,
+            "`main` can't take arguments",
+        ));
+    }
+    if !sig.generics.params.is_empty() || sig.generics.where_clause.is_some() {
+        return Err(Error::new_spanned(&sig.generics, "`main` can't be generic"));
+    }
+    if !matches!(&sig.output, ReturnType::Type(_, ty) if matches!(**ty, Type::Never(_))) {
+        return Err(Error::new_spanned(
+            &sig,
+            "`main` must never return, e.g. `async fn main() -> !`",
+        ));
+    }
+
+    let name = &sig.ident;
+    let output = &sig.output;
+    Ok(quote! {
+        #(#attrs)*
+        #[::async_hal::__private::cortex_m_rt::entry]
+        #vis fn #name() #output {
+            async fn main() #output #block
+
+            ::async_hal::block_on(main(), ::async_hal::__private::cortex_m::asm::wfi)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand;
+    use quote::quote;
+    use syn::{ItemFn, ReturnType, Type};
+
+    #[test]
+    fn it_creates_an_entry_point() {
+        let output = expand(
+            quote!(),
+            quote! {
+                async fn main() -> ! {
+                    loop {}
+                }
+            },
+        )
+        .unwrap();
+
+        let main: ItemFn = syn::parse2(output).unwrap();
+        assert!(main.sig.asyncness.is_none());
+        assert!(
+            matches!(main.sig.output, ReturnType::Type(_, ty) if matches!(*ty, Type::Never(_)))
+        );
+        assert!(main.attrs[0].path().segments.last().unwrap().ident == "entry");
+    }
+
+    #[test]
+    fn it_rejects_invalid_signatures() {
+        assert!(expand(
+            quote!(stack = 1024),
+            quote!(
+                async fn main() -> ! {
+                    loop {}
+                }
+            )
+        )
+        .is_err());
+
+        for item in [
+            quote!(
+                fn main() -> ! {
+                    loop {}
+                }
+            ),
+            quote!(
+                async fn main() {}
+            ),
+            quote!(
+                async fn main(value: u8) -> ! {
+                    loop {}
+                }
+            ),
+        ] {
+            assert!(expand(quote!(), item).is_err());
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Attribute macros for [`async-hal`](https://docs.rs/async-hal).
+//!
+//! These are re-exported by `async-hal` with the `macros` feature
+//! and should be used through it as `#[async_hal::task]` and `#[async_hal::main]`.
+
+use proc_macro::TokenStream;
+
+mod entry;
+mod task;
+
+/// Run an `async fn` on its own interrupt handler.
+///
+/// The function is replaced with one that takes the same arguments,
+/// creates the task in static storage and spawns it on a static executor.
+/// It then polls the task once and unmasks the interrupt,
+/// whose handler polls the executor every time it runs.
+///
+/// The interrupt is given as a variant of the device's interrupt enum,
+/// and its handler is defined with `cortex_m_rt`'s `#[interrupt]`.
+/// Tasks must return `()` and be [`Send`], and each one can only be spawned once.
+/// ```ignore
+/// use stm32f1xx_hal::pac;
+///
+/// #[async_hal::task(interrupt = pac::Interrupt::USART3)]
+/// async fn echo(reader: Reader) {
+///     // ...
+/// }
+///
+/// // In `main`
+/// echo(reader).unwrap();
+/// ```
+#[proc_macro_attribute]
+pub fn task(args: TokenStream, item: TokenStream) -> TokenStream {
+    task::expand(args.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Run an `async fn main` as the entry point of the program.
+///
+/// This creates a `cortex_m_rt` entry point that runs the future with `async_hal::block_on`,
+/// sleeping with `wfi` until the next interrupt whenever it's pending.
+/// ```ignore
+/// #[async_hal::main]
+/// async fn main() -> ! {
+///     loop {
+///         led.toggle();
+///         timer.delay_ms(1_000).await.unwrap();
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn main(args: TokenStream, item: TokenStream) -> TokenStream {
+    entry::expand(args.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
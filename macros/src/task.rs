@@ -0,0 +1,209 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{parse::Parser, Error, FnArg, ItemFn, Pat, Path, ReturnType, Type};
+
+/// Expand `#[task(interrupt = pac::Interrupt::NAME)]` into a function that spawns the task
+/// on a static executor polled by the `NAME` interrupt handler.
+pub fn expand(args: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let mut interrupt: Option<Path> = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("interrupt") {
+            interrupt = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("unsupported task argument"))
+        }
+    });
+    parser.parse2(args)?;
+    let interrupt = interrupt.ok_or_else(|| {
+        Error::new(
+            Span::call_site(),
+            "missing interrupt argument, e.g. `#[task(interrupt = pac::Interrupt::USART3)]`",
+        )
+    })?;
+
+    // Split the path into the interrupt enum of the device and the name of its vector
+    let mut enum_path = interrupt.clone();
+    let vector = match enum_path.segments.pop() {
+        Some(vector) if !enum_path.segments.is_empty() => vector.into_value().ident,
+        _ => {
+            return Err(Error::new_spanned(
+                &interrupt,
+                "interrupt must be a variant of the device's interrupt enum, e.g. `pac::Interrupt::USART3`",
+            ))
+        }
+    };
+    enum_path.segments.pop_punct();
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = syn::parse2(item)?;
+
+    if sig.asyncness.is_none() {
+        return Err(Error::new_spanned(sig.fn_token, "tasks must be `async fn`"));
+    }
+    if !sig.generics.params.is_empty() || sig.generics.where_clause.is_some() {
+        return Err(Error::new_spanned(
+            &sig.generics,
+            "tasks can't be generic, their futures are stored in a `static`",
+        ));
+    }
+    if let ReturnType::Type(_, ty) = &sig.output {
+        if !matches!(&**ty, Type::Tuple(tuple) if tuple.elems.is_empty()) {
+            return Err(Error::new_spanned(ty, "tasks must return `()`"));
+        }
+    }
+
+    let mut names = Vec::new();
+    let mut tys = Vec::new();
+    for input in &sig.inputs {
+        let FnArg::Typed(arg) = input else {
+            return Err(Error::new_spanned(input, "tasks can't take `self`"));
+        };
+        match &*arg.pat {
+            Pat::Ident(pat) if pat.by_ref.is_none() && pat.subpat.is_none() => {
+                names.push(&pat.ident);
+                tys.push(&arg.ty);
+            }
+            pat => {
+                return Err(Error::new_spanned(
+                    pat,
+                    "task arguments must be plain identifiers",
+                ))
+            }
+        }
+    }
+
+    let name = &sig.ident;
+    Ok(quote! {
+        #(#attrs)*
+        #vis fn #name(
+            #(#names: #tys),*
+        ) -> ::core::result::Result<(), ::async_hal::executor::SpawnError> {
+            // `#[interrupt]` checks the vector exists in the `interrupt` enum
+            use #enum_path as interrupt;
+
+            static EXECUTOR: ::async_hal::executor::StaticExecutor<
+                ::async_hal::executor::Arena<::async_hal::executor::Nvic<#enum_path>, 1>,
+            > = ::async_hal::executor::StaticExecutor::arena(
+                ::async_hal::executor::Nvic::new(#interrupt),
+            );
+
+            #[::async_hal::__private::cortex_m_rt::interrupt]
+            fn #vector() {
+                _ = EXECUTOR.poll();
+            }
+
+            ::async_hal::static_task! {
+                async fn task(#(#names: #tys),*) #block
+            }
+
+            let task = task(#(#names),*).ok_or(::async_hal::executor::SpawnError)?;
+            EXECUTOR
+                .spawn(task)
+                .map_err(|_| ::async_hal::executor::SpawnError)?;
+            _ = EXECUTOR.poll();
+
+            // Safety: the task is spawned, so its handler can start polling the executor
+            unsafe { ::async_hal::__private::cortex_m::peripheral::NVIC::unmask(#interrupt) };
+
+            ::core::result::Result::Ok(())
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand;
+    use quote::quote;
+    use syn::{Item, ItemFn, Stmt};
+
+    #[test]
+    fn it_binds_the_interrupt_handler() {
+        let output = expand(
+            quote!(interrupt = pac::Interrupt::USART3),
+            quote! {
+                /// Echo serial data.
+                pub async fn echo(mut reader: Reader, count: usize) {
+                    reader.read(count).await;
+                }
+            },
+        )
+        .unwrap();
+
+        let spawn: ItemFn = syn::parse2(output).unwrap();
+        assert_eq!(spawn.sig.ident, "echo");
+        assert!(spawn.sig.asyncness.is_none());
+        assert_eq!(spawn.sig.inputs.len(), 2);
+        assert_eq!(spawn.attrs.len(), 1);
+
+        let handler = spawn
+            .block
+            .stmts
+            .iter()
+            .find_map(|stmt| match stmt {
+                Stmt::Item(Item::Fn(handler)) => Some(handler),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(handler.sig.ident, "USART3");
+        assert!(handler.attrs[0].path().segments.last().unwrap().ident == "interrupt");
+    }
+
+    #[test]
+    fn it_requires_an_interrupt() {
+        let error = expand(
+            quote!(),
+            quote!(
+                async fn echo() {}
+            ),
+        )
+        .unwrap_err();
+        assert!(error.to_string().contains("missing interrupt argument"));
+
+        let error = expand(
+            quote!(interrupt = USART3),
+            quote!(
+                async fn echo() {}
+            ),
+        )
+        .unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("variant of the device's interrupt enum"));
+
+        let error = expand(
+            quote!(priority = 2),
+            quote!(
+                async fn echo() {}
+            ),
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "unsupported task argument");
+    }
+
+    #[test]
+    fn it_rejects_invalid_signatures() {
+        for item in [
+            quote!(
+                fn echo() {}
+            ),
+            quote!(
+                async fn echo<T>(value: T) {}
+            ),
+            quote!(
+                async fn echo() -> u8 {
+                    0
+                }
+            ),
+            quote!(
+                async fn echo((a, b): (u8, u8)) {}
+            ),
+        ] {
+            assert!(expand(quote!(interrupt = pac::Interrupt::USART3), item).is_err());
+        }
+    }
+}
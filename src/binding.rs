@@ -0,0 +1,205 @@
+//! Bindings between interrupt handlers and the drivers they wake.
+
+use core::marker::PhantomData;
+#[cfg(feature = "nb")]
+use embedded_hal::timer::{Cancel, CountDown, Periodic};
+pub use futures::task::AtomicWaker;
+
+/// Driver that's woken by the handler of a single interrupt vector.
+pub trait Driver {
+    /// Marker type of the interrupt vector that wakes this driver.
+    type Interrupt;
+}
+
+/// Driver `D` woken by the handler of interrupt `I`.
+///
+/// Drivers from other crates, such as HAL peripherals, can't implement [`Driver`] in the application
+/// because of the orphan rule, so they're wrapped in this type instead.
+/// It forwards the driver traits of this crate and `embedded_hal` to the wrapped driver.
+/// ```
+/// use async_hal::binding::{Driver, On};
+///
+/// // HAL crate
+/// #[allow(non_camel_case_types)]
+/// pub enum TIM2 {}
+/// pub struct Counter;
+///
+/// // Application
+/// fn interrupt_of<D: Driver<Interrupt = TIM2>>(_driver: &D) {}
+///
+/// let counter: On<TIM2, _> = On::new(Counter);
+/// interrupt_of(&counter);
+/// ```
+pub struct On<I, D> {
+    driver: D,
+    _interrupt: PhantomData<fn() -> I>,
+}
+
+impl<I, D> On<I, D> {
+    /// Wrap `driver`, which is woken by the handler of interrupt `I`.
+    pub const fn new(driver: D) -> Self {
+        Self {
+            driver,
+            _interrupt: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the wrapped driver.
+    pub fn get_ref(&self) -> &D {
+        &self.driver
+    }
+
+    /// Returns a mutable reference to the wrapped driver.
+    pub fn get_mut(&mut self) -> &mut D {
+        &mut self.driver
+    }
+
+    /// Returns the wrapped driver.
+    pub fn into_inner(self) -> D {
+        self.driver
+    }
+}
+
+impl<I, D> Driver for On<I, D> {
+    type Interrupt = I;
+}
+
+#[cfg(feature = "can")]
+impl<I, D: crate::can::receive::Receive> crate::can::receive::Receive for On<I, D> {
+    type Frame = D::Frame;
+    type Error = D::Error;
+
+    fn receive(&mut self) -> nb::Result<Self::Frame, Self::Error> {
+        self.driver.receive()
+    }
+}
+
+#[cfg(feature = "nb")]
+impl<I, D: CountDown> CountDown for On<I, D> {
+    type Time = D::Time;
+
+    fn start<T>(&mut self, count: T)
+    where
+        T: Into<Self::Time>,
+    {
+        self.driver.start(count)
+    }
+
+    fn wait(&mut self) -> nb::Result<(), void::Void> {
+        self.driver.wait()
+    }
+}
+
+#[cfg(feature = "nb")]
+impl<I, D: Periodic> Periodic for On<I, D> {}
+
+#[cfg(feature = "nb")]
+impl<I, D: Cancel> Cancel for On<I, D> {
+    type Error = D::Error;
+
+    fn cancel(&mut self) -> Result<(), Self::Error> {
+        self.driver.cancel()
+    }
+}
+
+/// Token proving that the handler of interrupt `I` wakes the [`AtomicWaker`] of driver `D`.
+///
+/// This is implemented by the tokens declared with [`bind_interrupts!`](crate::bind_interrupts),
+/// so passing a token that doesn't bind a driver to its [`Driver::Interrupt`] is a compile error.
+pub trait Binding<I, D> {
+    /// Returns the waker woken by the bound interrupt handler.
+    fn waker() -> &'static AtomicWaker;
+}
+
+/// Declare a token type that binds interrupt handlers to the drivers they wake.
+///
+/// Each `INTERRUPT => Driver, ...;` line generates a `cortex-m-rt` `#[interrupt]` handler for the `INTERRUPT` vector
+/// and a static [`AtomicWaker`] for each driver type it's bound to.
+/// The handler wakes those wakers, and drivers register with them through the token's [`Binding`] impls.
+///
+/// Like `#[interrupt]`, the device's interrupt enum must be in scope as `interrupt`,
+/// so the vector is checked to exist.
+/// A marker type named after each vector must also be in scope, usually provided by the HAL,
+/// and it must be the [`Driver::Interrupt`] of the drivers bound to it.
+/// Drivers from other crates are bound by wrapping them in [`On`].
+/// ```
+/// use async_hal::binding::{AtomicWaker, Binding, Driver};
+///
+/// // Device crate
+/// #[allow(non_camel_case_types)]
+/// pub enum interrupt {
+///     CAN_RX0,
+///     CAN_RX1,
+/// }
+///
+/// // HAL crate
+/// #[allow(non_camel_case_types)]
+/// pub enum CAN_RX0 {}
+/// #[allow(non_camel_case_types)]
+/// pub enum CAN_RX1 {}
+///
+/// struct Rx0;
+/// struct Rx1;
+///
+/// impl Driver for Rx0 {
+///     type Interrupt = CAN_RX0;
+/// }
+///
+/// impl Driver for Rx1 {
+///     type Interrupt = CAN_RX1;
+/// }
+///
+/// fn waker<D, B>(_driver: &D, _irqs: B) -> &'static AtomicWaker
+/// where
+///     D: Driver,
+///     B: Binding<D::Interrupt, D>,
+/// {
+///     B::waker()
+/// }
+///
+/// // Application
+/// async_hal::bind_interrupts! {
+///     /// CAN receive interrupts
+///     struct Irqs {
+///         CAN_RX0 => Rx0;
+///         CAN_RX1 => Rx1;
+///     }
+/// }
+///
+/// assert!(!core::ptr::eq(waker(&Rx0, Irqs), waker(&Rx1, Irqs)));
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "rt")))]
+#[cfg(feature = "rt")]
+#[macro_export]
+macro_rules! bind_interrupts {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident {
+            $($irq:ident => $($driver:ty),+;)*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug)]
+        $vis struct $name;
+
+        $(
+            #[allow(non_snake_case)]
+            #[$crate::__private::cortex_m_rt::interrupt]
+            fn $irq() {
+                $(
+                    <$name as $crate::binding::Binding<$irq, $driver>>::waker().wake();
+                )+
+            }
+
+            $(
+                impl $crate::binding::Binding<$irq, $driver> for $name {
+                    fn waker() -> &'static $crate::binding::AtomicWaker {
+                        static WAKER: $crate::binding::AtomicWaker =
+                            $crate::binding::AtomicWaker::new();
+                        &WAKER
+                    }
+                }
+            )+
+        )*
+    };
+}
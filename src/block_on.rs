@@ -0,0 +1,186 @@
+use core::{
+    pin::pin,
+    ptr,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+use futures::Future;
+
+/// Wake counter claimed by a call to [`block_on`] or [`run`] while it's in progress.
+///
+/// Wakers only ever increment their counter, so each call compares it
+/// with the value from before its last poll instead of resetting a flag.
+/// Wakers can outlive their call, so counters live in a static pool and are reused by later calls,
+/// where a stale wake can cause an extra poll, but a wake is never lost.
+struct Wakes {
+    is_claimed: AtomicBool,
+    count: AtomicUsize,
+}
+
+/// Number of calls that can run at once with their own counter.
+/// Further calls share the last counter.
+const MAX_CALLS: usize = 4;
+
+static WAKES: [Wakes; MAX_CALLS + 1] = [const {
+    Wakes {
+        is_claimed: AtomicBool::new(false),
+        count: AtomicUsize::new(0),
+    }
+}; MAX_CALLS + 1];
+
+/// Releases a claimed counter when dropped, even if a poll panics.
+struct Claim(&'static Wakes);
+
+impl Claim {
+    fn new() -> Self {
+        let (shared, own) = WAKES.split_last().unwrap();
+        let wakes = own
+            .iter()
+            .find(|wakes| !wakes.is_claimed.swap(true, Ordering::Acquire))
+            .unwrap_or(shared);
+        Self(wakes)
+    }
+}
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        if !ptr::eq(self.0, WAKES.last().unwrap()) {
+            self.0.is_claimed.store(false, Ordering::Release);
+        }
+    }
+}
+
+/// Vtable of [`block_on`] wakers, which also record wakes in its stats.
+static VTABLE: RawWakerVTable = RawWakerVTable::new(clone_waker, wake, wake, drop_waker);
+
+/// Vtable of [`run`] wakers.
+#[cfg(feature = "executor")]
+static RUN_VTABLE: RawWakerVTable =
+    RawWakerVTable::new(clone_run_waker, wake_run, wake_run, drop_waker);
+
+fn clone_waker(ptr: *const ()) -> RawWaker {
+    RawWaker::new(ptr, &VTABLE)
+}
+
+#[cfg(feature = "executor")]
+fn clone_run_waker(ptr: *const ()) -> RawWaker {
+    RawWaker::new(ptr, &RUN_VTABLE)
+}
+
+fn wake(ptr: *const ()) {
+    wake_run(ptr);
+
+    #[cfg(feature = "stats")]
+    crate::stats::BLOCK_ON.record_wake();
+}
+
+fn wake_run(ptr: *const ()) {
+    // Safety: wakers are created from a `&'static Wakes` in `run_with`
+    let wakes = unsafe { &*ptr.cast::<Wakes>() };
+    wakes.count.fetch_add(1, Ordering::AcqRel);
+}
+
+fn drop_waker(_ptr: *const ()) {}
+
+/// Call `poll` until it's ready, calling `wait()` whenever its waker wasn't woken during the last poll.
+#[cfg(feature = "executor")]
+pub(crate) fn run<T>(poll: impl FnMut(&mut Context) -> Poll<T>, wait: impl FnMut()) -> T {
+    run_with(&RUN_VTABLE, poll, wait)
+}
+
+fn run_with<T>(
+    vtable: &'static RawWakerVTable,
+    mut poll: impl FnMut(&mut Context) -> Poll<T>,
+    mut wait: impl FnMut(),
+) -> T {
+    let claim = Claim::new();
+    let raw_waker = RawWaker::new(claim.0 as *const Wakes as *const (), vtable);
+
+    // Safety: the counter is `'static` and the vtable functions uphold the `RawWaker` contract
+    let waker = unsafe { Waker::from_raw(raw_waker) };
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        let wakes = claim.0.count.load(Ordering::Acquire);
+        if let Poll::Ready(output) = poll(&mut cx) {
+            return output;
+        }
+
+        if claim.0.count.load(Ordering::Acquire) == wakes {
+            wait()
+        }
+    }
+}
+
+/// Run `future` to completion and return its output.
+/// This will poll the future, calling `wait()` whenever it's pending
+/// and hasn't been woken since it was last polled.
+///
+/// This is useful for microcontrollers that can be set into a low-power mode while waiting,
+/// such as using Cortex-M's `wfi` instruction.
+/// Wakers can be woken from interrupt handlers;
+/// a wake that happens after the check but before `wait()` is only noticed once `wait()` returns,
+/// which `wfi` does for any interrupt.
+/// ```
+/// let task = async { true };
+///
+/// let output = async_hal::block_on(task, || {
+///     dbg!("Waiting!");
+/// });
+/// assert!(output);
+/// ```
+pub fn block_on<F, W>(future: F, wait: W) -> F::Output
+where
+    F: Future,
+    W: FnMut(),
+{
+    let mut future = pin!(future);
+
+    #[cfg(feature = "stats")]
+    crate::stats::BLOCK_ON.reset();
+
+    run_with(
+        &VTABLE,
+        |cx| {
+            #[cfg(feature = "stats")]
+            let poll = crate::stats::BLOCK_ON.measure(|| crate::task::poll(future.as_mut(), cx));
+            #[cfg(not(feature = "stats"))]
+            let poll = crate::task::poll(future.as_mut(), cx);
+
+            poll
+        },
+        wait,
+    )
+}
+
+/// Run `future` to completion like [`block_on`],
+/// passing the earliest pending deadline of `deadlines` to `idle` instead of calling `wait()`.
+///
+/// Deadlines are reported by [`Tracked`](crate::delay::Tracked) delays as instants of the registry's clock,
+/// so `idle` can choose between `wfi`, deep sleep or programming a wakeup timer for the deadline.
+/// It's called with `None` if no tracked delay is pending.
+/// ```
+/// use async_hal::delay::{self, Deadlines, DelayMs, FreeRunning, Tracked};
+///
+/// let deadlines: Deadlines<_, 1_000> = Deadlines::new(FreeRunning::new(|| 0));
+/// let mut delay = Tracked::new(delay::ready::<u32>(), &deadlines).unwrap();
+///
+/// let output = async_hal::block_on_idle(delay.delay_ms(10), &deadlines, |deadline| {
+///     // Program a wakeup for `deadline`, then sleep
+///     dbg!(deadline);
+/// });
+/// assert!(output.is_ok());
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "delay")))]
+#[cfg(feature = "delay")]
+pub fn block_on_idle<F, M, W, const HZ: u32, const N: usize>(
+    future: F,
+    deadlines: &crate::delay::Deadlines<M, HZ, N>,
+    mut idle: W,
+) -> F::Output
+where
+    F: Future,
+    W: FnMut(Option<crate::delay::Instant<HZ>>),
+{
+    block_on(future, || idle(deadlines.next_deadline()))
+}
@@ -1,3 +1,7 @@
+#[cfg(feature = "delay")]
+use crate::delay::{self, DelayMs, Timeout};
+#[cfg(feature = "delay")]
+use futures::{stream::Next, StreamExt};
 use futures::{Sink, Stream};
 
 #[cfg(feature = "nb")]
@@ -24,12 +28,28 @@ pub trait CanReceive:
     type Frame: Frame;
 
     type Error;
+
+    /// Receive the next frame, giving up if none arrives before a delay of `ms` milliseconds expires.
+    /// See [`delay::timeout`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "delay")))]
+    #[cfg(feature = "delay")]
+    fn receive_timeout<'a, D>(
+        &'a mut self,
+        delay: &'a mut D,
+        ms: D::Delay,
+    ) -> Timeout<'a, D, Next<'a, Self>>
+    where
+        Self: Unpin,
+        D: DelayMs + Unpin,
+    {
+        delay::timeout(delay, ms, self.next())
+    }
 }
 
 impl<T, F, E> CanReceive for T
 where
     T: ?Sized + Stream<Item = Result<F, E>>,
-    F: ?Sized + Frame,
+    F: Frame,
 {
     type Frame = F;
 
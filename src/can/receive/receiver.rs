@@ -1,9 +1,13 @@
 use super::Receive;
+use crate::{
+    binding::{Binding, Driver},
+    coop,
+};
 use core::{
     pin::Pin,
     task::{Context, Poll},
 };
-use futures::{task::AtomicWaker, Stream};
+use futures::{ready, task::AtomicWaker, Stream};
 
 pub struct Receiver<R> {
     receive: R,
@@ -14,6 +18,16 @@ impl<R> Receiver<R> {
     pub const fn new(receive: R, waker: &'static AtomicWaker) -> Self {
         Self { receive, waker }
     }
+
+    /// Create a new receiver woken by the [`Driver::Interrupt`] of `R` bound in `irqs`.
+    /// See [`bind_interrupts!`](crate::bind_interrupts).
+    pub fn bind<B>(receive: R, _irqs: B) -> Self
+    where
+        R: Driver,
+        B: Binding<R::Interrupt, R>,
+    {
+        Self::new(receive, B::waker())
+    }
 }
 
 impl<R> Stream for Receiver<R>
@@ -23,6 +37,8 @@ where
     type Item = Result<R::Frame, R::Error>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        ready!(coop::poll_proceed(cx));
+
         match self.receive.receive() {
             Ok(frame) => Poll::Ready(Some(Ok(frame))),
             Err(nb::Error::WouldBlock) => {
@@ -37,12 +53,34 @@ where
 pub struct DualReceiver<T, U> {
     rx0: T,
     rx1: U,
-    waker: &'static AtomicWaker,
+    wakers: [&'static AtomicWaker; 2],
 }
 
 impl<T, U> DualReceiver<T, U> {
     pub const fn new(rx0: T, rx1: U, waker: &'static AtomicWaker) -> Self {
-        Self { rx0, rx1, waker }
+        Self {
+            rx0,
+            rx1,
+            wakers: [waker; 2],
+        }
+    }
+
+    /// Create a new receiver woken by the [`Driver::Interrupt`]s of `T` and `U` bound in `irqs`.
+    /// See [`bind_interrupts!`](crate::bind_interrupts).
+    pub fn bind<B>(rx0: T, rx1: U, _irqs: B) -> Self
+    where
+        T: Driver,
+        U: Driver,
+        B: Binding<T::Interrupt, T> + Binding<U::Interrupt, U>,
+    {
+        Self {
+            rx0,
+            rx1,
+            wakers: [
+                <B as Binding<T::Interrupt, T>>::waker(),
+                <B as Binding<U::Interrupt, U>>::waker(),
+            ],
+        }
     }
 }
 
@@ -54,12 +92,16 @@ where
     type Item = Result<T::Frame, T::Error>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        ready!(coop::poll_proceed(cx));
+
         match self.rx0.receive() {
             Ok(frame) => Poll::Ready(Some(Ok(frame))),
             Err(nb::Error::WouldBlock) => match self.rx1.receive() {
                 Ok(frame) => Poll::Ready(Some(Ok(frame))),
                 Err(nb::Error::WouldBlock) => {
-                    self.waker.register(cx.waker());
+                    for waker in self.wakers {
+                        waker.register(cx.waker());
+                    }
                     Poll::Pending
                 }
                 Err(nb::Error::Other(error)) => Poll::Ready(Some(Err(error))),
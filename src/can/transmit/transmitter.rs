@@ -1,9 +1,10 @@
 use super::Transmit;
+use crate::coop;
 use core::{
     pin::Pin,
     task::{Context, Poll},
 };
-use futures::Sink;
+use futures::{ready, Sink};
 
 /// Transmitter sink for frames to a CAN bus.
 pub struct Transmitter<T, F> {
@@ -27,7 +28,9 @@ where
 {
     type Error = T::Error;
 
-    fn poll_ready(mut self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        ready!(coop::poll_proceed(cx));
+
         if self.transmit.is_ready() {
             Poll::Ready(Ok(()))
         } else {
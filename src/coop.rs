@@ -0,0 +1,152 @@
+//! Cooperative scheduling budget.
+//!
+//! Hardware streams like [`serial::Reader`](crate::serial::Reader) return `Ready` for as long as data is available,
+//! so a task draining a busy bus could run forever without returning to its executor,
+//! starving every other future in the same `select!` and every other task on the same interrupt.
+//!
+//! To prevent this, executors and [`block_on`](crate::block_on) give each poll of a task a budget of [`BUDGET`] operations.
+//! The built-in streams consume one unit with [`poll_proceed`] every time they're polled for an item,
+//! and sinks every time they're polled for readiness to accept one,
+//! and once the budget is exhausted they wake the task and return `Pending` instead,
+//! letting the executor move on before polling it again.
+//! The budget is carried by the waker of the [`Context`] each poll is given, so it's never shared between polls,
+//! and outside of an executor the budget is unconstrained.
+//!
+//! Tasks that run long loops without using any of the built-in streams can call [`yield_now`] instead.
+
+use core::{
+    mem::ManuallyDrop,
+    pin::Pin,
+    ptr,
+    sync::atomic::{AtomicU8, Ordering},
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+use futures::Future;
+
+/// Number of operations a task can perform in a single poll.
+pub const BUDGET: u8 = 32;
+
+/// Budget of a single poll, reached through the waker of the [`Context`] passed to the task.
+///
+/// Each poll gets its own budget on the stack of [`with_budget`],
+/// so nested polls and polls on other cores or threads never share one.
+struct Budget<'a> {
+    remaining: AtomicU8,
+    waker: &'a Waker,
+}
+
+/// Vtable of the waker that carries a [`Budget`], forwarding to the waker it wraps.
+static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake_by_ref, wake_by_ref, drop);
+
+/// Clones the wrapped waker, so clones don't borrow the budget.
+///
+/// # Safety
+/// `ptr` must point to the [`Budget`] of a [`with_budget`] call in progress.
+unsafe fn clone(ptr: *const ()) -> RawWaker {
+    let budget = unsafe { &*ptr.cast::<Budget>() };
+    let waker = ManuallyDrop::new(budget.waker.clone());
+    RawWaker::new(waker.data(), waker.vtable())
+}
+
+/// # Safety
+/// `ptr` must point to the [`Budget`] of a [`with_budget`] call in progress.
+unsafe fn wake_by_ref(ptr: *const ()) {
+    let budget = unsafe { &*ptr.cast::<Budget>() };
+    budget.waker.wake_by_ref();
+}
+
+/// Nothing to release, the budget is owned by [`with_budget`].
+unsafe fn drop(_ptr: *const ()) {}
+
+/// Returns the budget of the poll `cx` was created for, or `None` if it's unconstrained.
+fn budget<'a>(cx: &'a Context) -> Option<&'a Budget<'a>> {
+    let waker = cx.waker();
+    if !ptr::eq(waker.vtable(), &VTABLE) {
+        return None;
+    }
+
+    // Safety: wakers with this vtable are only created by `with_budget` from a `Budget` that outlives `cx`
+    Some(unsafe { &*waker.data().cast::<Budget>() })
+}
+
+/// Run `f` with a fresh budget carried by the waker of its [`Context`].
+///
+/// Executors call this around every poll of a task.
+/// The budget only lives as long as `f`, and wakers cloned from the context wake the waker of `cx` directly.
+pub fn with_budget<R>(cx: &mut Context, f: impl FnOnce(&mut Context) -> R) -> R {
+    let budget = Budget {
+        remaining: AtomicU8::new(BUDGET),
+        waker: cx.waker(),
+    };
+    let raw_waker = RawWaker::new(&budget as *const Budget as *const (), &VTABLE);
+
+    // Safety: the budget outlives the waker, which is only lent to `f`,
+    // and the vtable functions uphold the `RawWaker` contract
+    let waker = unsafe { Waker::from_raw(raw_waker) };
+    f(&mut Context::from_waker(&waker))
+}
+
+/// Returns `true` if the task polled with `cx` can still make progress in this poll.
+pub fn has_budget_remaining(cx: &Context) -> bool {
+    match budget(cx) {
+        Some(budget) => budget.remaining.load(Ordering::Relaxed) > 0,
+        None => true,
+    }
+}
+
+/// Consume one unit of the budget of the task polled with `cx`.
+///
+/// This returns `Poll::Pending` and wakes the task if the budget is exhausted.
+pub fn poll_proceed(cx: &mut Context) -> Poll<()> {
+    let Some(budget) = budget(cx) else {
+        return Poll::Ready(());
+    };
+
+    let update = budget
+        .remaining
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |remaining| {
+            remaining.checked_sub(1)
+        });
+    match update {
+        Ok(_) => Poll::Ready(()),
+        Err(_) => {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+/// Yield execution back to the executor, letting other tasks run before this one continues.
+/// ```
+/// let output = async_hal::block_on(
+///     async {
+///         async_hal::coop::yield_now().await;
+///         true
+///     },
+///     || {},
+/// );
+/// assert!(output);
+/// ```
+pub fn yield_now() -> YieldNow {
+    YieldNow { is_yielded: false }
+}
+
+/// Future for the [`yield_now`] function.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct YieldNow {
+    is_yielded: bool,
+}
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        if self.is_yielded {
+            return Poll::Ready(());
+        }
+
+        self.is_yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
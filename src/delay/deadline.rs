@@ -0,0 +1,149 @@
+use super::{DelayMs, Duration, Instant, Monotonic};
+use core::{
+    cell::RefCell,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use critical_section::Mutex;
+use futures::ready;
+
+/// Default number of [`Tracked`] delays a [`Deadlines`] registry can hold at once.
+pub const MAX_TRACKED: usize = 16;
+
+#[derive(Clone, Copy)]
+enum Slot<const HZ: u32> {
+    Free,
+    Idle,
+    Pending(Instant<HZ>),
+}
+
+/// Registry of the deadlines of pending [`Tracked`] delays, all measured by the same [`Monotonic`] clock.
+///
+/// The registry is passed to [`block_on_idle`](crate::block_on_idle) or
+/// [`Executor::poll_idle`](crate::Executor::poll_idle) so they can report the next deadline
+/// before sleeping. Delays using a different clock need a registry of their own,
+/// since their deadlines can't be compared.
+/// ```
+/// use async_hal::delay::{self, Deadlines, DelayMs, FreeRunning, Instant, Tracked};
+///
+/// let deadlines: Deadlines<_, 1_000> = Deadlines::new(FreeRunning::new(|| 0));
+///
+/// let mut delay = Tracked::new(delay::ready::<u32>(), &deadlines).unwrap();
+/// assert!(delay.start(50).is_ok());
+/// assert_eq!(deadlines.next_deadline(), Some(Instant::from_ticks(50)));
+///
+/// assert!(delay.cancel().is_ok());
+/// assert_eq!(deadlines.next_deadline(), None);
+/// ```
+pub struct Deadlines<M, const HZ: u32, const N: usize = MAX_TRACKED> {
+    clock: M,
+    slots: Mutex<RefCell<[Slot<HZ>; N]>>,
+}
+
+impl<M, const HZ: u32, const N: usize> Deadlines<M, HZ, N> {
+    /// Create a new empty registry for delays measured by `clock`.
+    pub const fn new(clock: M) -> Self
+    where
+        M: Monotonic<Instant = Instant<HZ>, Duration = Duration<HZ>>,
+    {
+        Self {
+            clock,
+            slots: Mutex::new(RefCell::new([Slot::Free; N])),
+        }
+    }
+
+    /// Returns a reference to the clock of this registry.
+    pub fn clock(&self) -> &M {
+        &self.clock
+    }
+
+    /// Returns the earliest deadline of all pending [`Tracked`] delays.
+    pub fn next_deadline(&self) -> Option<Instant<HZ>> {
+        critical_section::with(|cs| {
+            self.slots
+                .borrow_ref(cs)
+                .iter()
+                .filter_map(|slot| match slot {
+                    Slot::Pending(deadline) => Some(*deadline),
+                    _ => None,
+                })
+                .min()
+        })
+    }
+
+    fn set_slot(&self, index: usize, slot: Slot<HZ>) {
+        critical_section::with(|cs| self.slots.borrow_ref_mut(cs)[index] = slot)
+    }
+}
+
+/// Error returned by [`Tracked::new`] when its [`Deadlines`] registry is full.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TooManyTracked {
+    _priv: (),
+}
+
+/// Delay that reports its deadline to a [`Deadlines`] registry while it's pending.
+pub struct Tracked<'a, T, M, const HZ: u32, const N: usize = MAX_TRACKED> {
+    delay: T,
+    deadlines: &'a Deadlines<M, HZ, N>,
+    slot: usize,
+}
+
+impl<'a, T, M, const HZ: u32, const N: usize> Tracked<'a, T, M, HZ, N> {
+    /// Track the deadlines of `delay` in `deadlines`,
+    /// using the clock of the registry to get the time they're started.
+    pub fn new(delay: T, deadlines: &'a Deadlines<M, HZ, N>) -> Result<Self, TooManyTracked> {
+        let slot = critical_section::with(|cs| {
+            let mut slots = deadlines.slots.borrow_ref_mut(cs);
+            let slot = slots.iter().position(|slot| matches!(slot, Slot::Free))?;
+            slots[slot] = Slot::Idle;
+            Some(slot)
+        });
+
+        slot.map(|slot| Self {
+            delay,
+            deadlines,
+            slot,
+        })
+        .ok_or(TooManyTracked { _priv: () })
+    }
+
+    /// Returns a reference to the underlying delay.
+    pub fn get_ref(&self) -> &T {
+        &self.delay
+    }
+}
+
+impl<T, M, const HZ: u32, const N: usize> DelayMs for Tracked<'_, T, M, HZ, N>
+where
+    T: DelayMs + Unpin,
+    T::Delay: Copy + Into<u64>,
+    M: Monotonic<Instant = Instant<HZ>, Duration = Duration<HZ>>,
+{
+    type Delay = T::Delay;
+    type Error = T::Error;
+
+    fn start(&mut self, ms: Self::Delay) -> Result<(), Self::Error> {
+        self.delay.start(ms)?;
+        let deadline = self.deadlines.clock.now() + Duration::<HZ>::millis(ms.into());
+        self.deadlines.set_slot(self.slot, Slot::Pending(deadline));
+        Ok(())
+    }
+
+    fn poll_delay_ms(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        let result = ready!(self.delay.poll_delay_ms_unpin(cx));
+        self.deadlines.set_slot(self.slot, Slot::Idle);
+        Poll::Ready(result)
+    }
+
+    fn cancel(&mut self) -> Result<(), Self::Error> {
+        self.deadlines.set_slot(self.slot, Slot::Idle);
+        self.delay.cancel()
+    }
+}
+
+impl<T, M, const HZ: u32, const N: usize> Drop for Tracked<'_, T, M, HZ, N> {
+    fn drop(&mut self) {
+        self.deadlines.set_slot(self.slot, Slot::Free);
+    }
+}
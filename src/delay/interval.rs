@@ -0,0 +1,158 @@
+use super::{DelayMs, Duration, Instant, Monotonic, Periodic};
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures::Stream;
+
+/// Behavior of an [`Interval`] when it misses ticks,
+/// such as when the consumer is busy for longer than a period.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MissedTickBehavior {
+    /// Yield every missed tick as soon as possible to catch up, keeping the original schedule.
+    #[default]
+    Burst,
+
+    /// Restart the schedule from the late tick, so the next tick is a full period after it.
+    Delay,
+
+    /// Drop the missed ticks and continue at the next tick of the original schedule.
+    Skip,
+}
+
+/// Stream of ticks every `period` from a [`Periodic`] timer.
+///
+/// Ticks are woken by the timer, while the [`Monotonic`] clock that ticks at `HZ` is used to count the ticks missed
+/// when the stream isn't polled for longer than a period.
+/// These are handled according to the [`MissedTickBehavior`] of the interval, which defaults to [`MissedTickBehavior::Burst`].
+/// Like `tokio::time::Interval`, the first tick completes immediately.
+///
+/// The timer is cancelled when the interval is dropped.
+pub struct Interval<T: DelayMs, M, const HZ: u32> {
+    timer: T,
+    clock: M,
+    period: Duration<HZ>,
+    behavior: MissedTickBehavior,
+    // Deadline of the next tick, or `None` before the first tick
+    next: Option<Instant<HZ>>,
+    // Missed ticks left to yield in a burst
+    missed: u64,
+}
+
+impl<T: DelayMs, M, const HZ: u32> Interval<T, M, HZ> {
+    /// Create a new interval that ticks every `period`,
+    /// using `clock` to detect missed ticks.
+    ///
+    /// # Panics
+    /// Panics if `period` is zero.
+    pub fn new(timer: T, clock: M, period: Duration<HZ>) -> Self {
+        assert!(period.ticks() > 0, "`period` must be non-zero");
+
+        Self {
+            timer,
+            clock,
+            period,
+            behavior: MissedTickBehavior::default(),
+            next: None,
+            missed: 0,
+        }
+    }
+
+    /// Returns the behavior of this interval when it misses ticks.
+    pub fn missed_tick_behavior(&self) -> MissedTickBehavior {
+        self.behavior
+    }
+
+    /// Set the behavior of this interval when it misses ticks.
+    pub fn set_missed_tick_behavior(&mut self, behavior: MissedTickBehavior) {
+        self.behavior = behavior;
+    }
+
+    /// Returns the period of this interval.
+    pub fn period(&self) -> Duration<HZ> {
+        self.period
+    }
+
+    /// Returns a reference to the underlying timer.
+    pub fn get_ref(&self) -> &T {
+        &self.timer
+    }
+}
+
+impl<T, M, const HZ: u32> Interval<T, M, HZ>
+where
+    T: DelayMs<Delay = u32> + Periodic + Unpin,
+    M: Monotonic<Instant = Instant<HZ>, Duration = Duration<HZ>>,
+{
+    /// Restart the interval, so the next tick is a full period from now.
+    pub fn reset(&mut self) -> Result<(), T::Error> {
+        self.start_timer()?;
+        self.next = Some(self.clock.now() + self.period);
+        self.missed = 0;
+        Ok(())
+    }
+
+    /// Poll for the next tick.
+    pub fn poll_tick(&mut self, cx: &mut Context) -> Poll<Result<(), T::Error>> {
+        let Some(next) = self.next else {
+            // The first tick completes immediately
+            return Poll::Ready(self.reset());
+        };
+
+        if self.missed > 0 {
+            self.missed -= 1;
+            return Poll::Ready(Ok(()));
+        }
+
+        if let Err(error) = futures::ready!(self.timer.poll_delay_ms_unpin(cx)) {
+            return Poll::Ready(Err(error));
+        }
+
+        let now = self.clock.now();
+        let missed = now
+            .checked_duration_since(next)
+            .map_or(0, |late| late.ticks() / self.period.ticks());
+        let after_missed = next + Duration::from_ticks((missed + 1) * self.period.ticks());
+
+        match self.behavior {
+            MissedTickBehavior::Burst => {
+                self.missed = missed;
+                self.next = Some(after_missed);
+            }
+            MissedTickBehavior::Delay if missed > 0 => {
+                // Realign the timer to this tick
+                self.start_timer()?;
+                self.next = Some(now + self.period);
+            }
+            MissedTickBehavior::Delay | MissedTickBehavior::Skip => {
+                self.next = Some(after_missed);
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_timer(&mut self) -> Result<(), T::Error> {
+        // Periods too long for the timer saturate
+        let ms = u32::try_from(self.period.to_millis()).unwrap_or(u32::MAX);
+        self.timer.start(ms)
+    }
+}
+
+impl<T, M, const HZ: u32> Stream for Interval<T, M, HZ>
+where
+    T: DelayMs<Delay = u32> + Periodic + Unpin,
+    M: Monotonic<Instant = Instant<HZ>, Duration = Duration<HZ>> + Unpin,
+{
+    type Item = Result<(), T::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_tick(cx).map(Some)
+    }
+}
+
+impl<T: DelayMs, M, const HZ: u32> Drop for Interval<T, M, HZ> {
+    fn drop(&mut self) {
+        self.timer.cancel().ok();
+    }
+}
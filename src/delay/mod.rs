@@ -6,9 +6,30 @@ use futures::{Future, FutureExt, Stream};
 
 pub use embedded_hal::timer::Periodic;
 
+mod deadline;
+pub use deadline::{Deadlines, TooManyTracked, Tracked, MAX_TRACKED};
+
+mod interval;
+pub use interval::{Interval, MissedTickBehavior};
+
+mod monotonic;
+#[cfg(feature = "mock")]
+pub use monotonic::MockClock;
+#[cfg(feature = "nb")]
+pub use monotonic::Ticker;
+pub use monotonic::{Counter, DelayUntil, Duration, FreeRunning, Instant, Monotonic};
+
+mod queue;
+pub use queue::{Alarm, QueueTimer, TimerQueue};
+
 mod ready;
 pub use ready::{ready, AlreadyStarted, Ready};
 
+mod timeout;
+pub use timeout::{
+    timeout, with_deadline, Elapsed, SinkTimeoutExt, Timeout, TimeoutError, WithDeadline,
+};
+
 #[cfg(feature = "nb")]
 mod timer;
 #[cfg(feature = "nb")]
@@ -40,10 +61,11 @@ pub trait DelayMs {
 
     /// Delay for `ms` milliseconds.
     /// Starts a new delay and returns a [`Future`] that completes when either the timer expires.
-    /// The returned future also implements [`Stream`] if this delay is [`Periodic`].
+    /// The returned future also implements [`Stream`] if this delay is [`Periodic`],
+    /// though [`Interval`] should be used for periodic ticks that may be missed.
     ///
     /// When dropped, this future will attempt to cancel the current delay.
-    fn delay_ms(&mut self, ms: Self::Delay) -> DelayMsFuture<Self>
+    fn delay_ms(&mut self, ms: Self::Delay) -> DelayMsFuture<'_, Self>
     where
         Self: Unpin,
     {
@@ -0,0 +1,385 @@
+use core::{
+    cell::Cell,
+    ops::{Add, Sub},
+    pin::Pin,
+    task::{Context, Poll},
+};
+use critical_section::Mutex;
+use futures::{task::AtomicWaker, Future};
+
+#[cfg(feature = "nb")]
+use core::cell::RefCell;
+#[cfg(feature = "nb")]
+use embedded_hal::timer::{CountDown, Periodic};
+
+#[cfg(feature = "mock")]
+use super::Alarm;
+#[cfg(feature = "mock")]
+use core::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "mock")]
+use std::sync::{Arc, Mutex as StdMutex};
+
+/// Instant of a clock that ticks at `HZ`.
+pub type Instant<const HZ: u32> = fugit::TimerInstantU64<HZ>;
+
+/// Duration in ticks of a clock that ticks at `HZ`.
+pub type Duration<const HZ: u32> = fugit::TimerDurationU64<HZ>;
+
+/// Monotonic clock that never goes backwards.
+///
+/// Unlike [`DelayMs`](super::DelayMs), delays are scheduled at absolute instants,
+/// so a periodic loop that waits until `last + period` doesn't drift by the time spent working.
+/// ```
+/// use async_hal::delay::{Duration, Instant, Monotonic};
+/// use core::cell::Cell;
+/// use futures::task::AtomicWaker;
+///
+/// struct Millis {
+///     ticks: Cell<u64>,
+///     // Woken by the tick interrupt
+///     waker: AtomicWaker,
+/// }
+///
+/// impl Monotonic for Millis {
+///     type Instant = Instant<1_000>;
+///     type Duration = Duration<1_000>;
+///
+///     fn now(&self) -> Self::Instant {
+///         Instant::from_ticks(self.ticks.get())
+///     }
+///
+///     fn waker(&self) -> Option<&AtomicWaker> {
+///         Some(&self.waker)
+///     }
+/// }
+///
+/// let clock = Millis {
+///     ticks: Cell::new(0),
+///     waker: AtomicWaker::new(),
+/// };
+/// let mut next = clock.now();
+/// let mut wakes = Vec::new();
+///
+/// let task = async {
+///     for _ in 0..3 {
+///         next += Duration::millis(10);
+///         clock.delay_until(next).await;
+///         wakes.push(clock.now().ticks());
+///
+///         // Work for 3ms
+///         clock.ticks.set(clock.ticks.get() + 3);
+///     }
+/// };
+/// async_hal::block_on(task, || {
+///     // Tick interrupt
+///     clock.ticks.set(clock.ticks.get() + 1);
+///     clock.waker.wake();
+/// });
+///
+/// assert_eq!(wakes, [10, 20, 30]);
+/// ```
+pub trait Monotonic {
+    /// Point in time of this clock.
+    type Instant: Copy
+        + Ord
+        + Add<Self::Duration, Output = Self::Instant>
+        + Sub<Output = Self::Duration>;
+
+    /// Span of time between two instants of this clock.
+    type Duration: Copy + Ord;
+
+    /// Returns the current instant.
+    fn now(&self) -> Self::Instant;
+
+    /// Returns the waker woken by an interrupt of this clock, such as a periodic tick, if it has one.
+    ///
+    /// Delays register with this waker, so their task is only polled again once the clock has moved.
+    /// It only holds the waker of the last task to poll a delay,
+    /// so clocks shared by several tasks should use a [`TimerQueue`](super::TimerQueue) instead.
+    fn waker(&self) -> Option<&AtomicWaker> {
+        None
+    }
+
+    /// Returns a [`Future`] that completes once `instant` has been reached.
+    ///
+    /// The future registers with [`Monotonic::waker`].
+    /// Clocks without a waker can't signal the instant,
+    /// so the future wakes its own task whenever it's pending and the task is busy-polled until then.
+    fn delay_until(&self, instant: Self::Instant) -> DelayUntil<'_, Self> {
+        DelayUntil {
+            clock: self,
+            instant,
+        }
+    }
+
+    /// Returns a [`Future`] that completes once `duration` has elapsed from now.
+    fn delay(&self, duration: Self::Duration) -> DelayUntil<'_, Self> {
+        self.delay_until(self.now() + duration)
+    }
+}
+
+impl<T: ?Sized + Monotonic> Monotonic for &T {
+    type Instant = T::Instant;
+    type Duration = T::Duration;
+
+    fn now(&self) -> Self::Instant {
+        (**self).now()
+    }
+
+    fn waker(&self) -> Option<&AtomicWaker> {
+        (**self).waker()
+    }
+}
+
+/// Future for [`Monotonic::delay_until`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct DelayUntil<'a, M: ?Sized + Monotonic> {
+    clock: &'a M,
+    instant: M::Instant,
+}
+
+impl<M: ?Sized + Monotonic> DelayUntil<'_, M> {
+    /// Returns the instant this delay completes at.
+    pub fn instant(&self) -> M::Instant {
+        self.instant
+    }
+}
+
+impl<M: ?Sized + Monotonic> Future for DelayUntil<'_, M> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        // Register before reading the clock so a tick in between still wakes this task
+        let waker = self.clock.waker();
+        if let Some(waker) = waker {
+            waker.register(cx.waker());
+        }
+
+        if self.clock.now() >= self.instant {
+            return Poll::Ready(());
+        }
+
+        if waker.is_none() {
+            // Nothing else will wake this task, so poll again
+            cx.waker().wake_by_ref();
+        }
+        Poll::Pending
+    }
+}
+
+/// Source of the raw ticks of a free-running hardware counter, such as the cycle counter.
+pub trait Counter {
+    /// Returns the current value of the counter, which wraps on overflow.
+    fn ticks(&self) -> u32;
+}
+
+impl<F> Counter for F
+where
+    F: Fn() -> u32,
+{
+    fn ticks(&self) -> u32 {
+        self()
+    }
+}
+
+/// [`Monotonic`] clock over a wrapping 32-bit [`Counter`] that ticks at `HZ`.
+///
+/// Overflows are counted when the clock is read, extending the counter to 64 bits,
+/// so [`Monotonic::now`] must be called at least once per wrap of the counter.
+/// ```
+/// use async_hal::delay::{FreeRunning, Monotonic};
+/// use core::cell::Cell;
+///
+/// let ticks = Cell::new(u32::MAX);
+/// let clock = FreeRunning::<_, 1_000_000>::new(|| ticks.get());
+/// assert_eq!(clock.now().ticks(), 0);
+///
+/// ticks.set(1);
+/// assert_eq!(clock.now().ticks(), 2);
+/// ```
+pub struct FreeRunning<C, const HZ: u32> {
+    counter: C,
+    state: Mutex<Cell<Option<(u32, u64)>>>,
+}
+
+impl<C, const HZ: u32> FreeRunning<C, HZ> {
+    /// Create a new clock from `counter`, starting at zero.
+    pub const fn new(counter: C) -> Self {
+        Self {
+            counter,
+            state: Mutex::new(Cell::new(None)),
+        }
+    }
+}
+
+impl<C: Counter, const HZ: u32> Monotonic for FreeRunning<C, HZ> {
+    type Instant = Instant<HZ>;
+    type Duration = Duration<HZ>;
+
+    fn now(&self) -> Self::Instant {
+        critical_section::with(|cs| {
+            let state = self.state.borrow(cs);
+            let ticks = self.counter.ticks();
+
+            // Add the ticks since the last read, including any overflow
+            let now = match state.get() {
+                Some((last, now)) => now + u64::from(ticks.wrapping_sub(last)),
+                None => 0,
+            };
+            state.set(Some((ticks, now)));
+
+            Instant::from_ticks(now)
+        })
+    }
+}
+
+/// [`Monotonic`] clock that counts the periods of a non-blocking [`Periodic`] timer, ticking at `HZ`.
+///
+/// The timer only reports one expired period at a time,
+/// so [`Monotonic::now`] must be called at least once per period, usually from the timer's interrupt handler.
+/// ```
+/// use async_hal::delay::{Monotonic, Ticker};
+/// use core::cell::Cell;
+/// use embedded_hal::timer::{CountDown, Periodic};
+///
+/// struct Timer<'a> {
+///     is_expired: &'a Cell<bool>,
+/// }
+///
+/// impl CountDown for Timer<'_> {
+///     type Time = u32;
+///
+///     fn start<T: Into<u32>>(&mut self, _count: T) {}
+///
+///     fn wait(&mut self) -> nb::Result<(), void::Void> {
+///         if self.is_expired.replace(false) {
+///             Ok(())
+///         } else {
+///             Err(nb::Error::WouldBlock)
+///         }
+///     }
+/// }
+///
+/// impl Periodic for Timer<'_> {}
+///
+/// let is_expired = Cell::new(false);
+/// let clock = Ticker::<_, 1_000>::new(Timer { is_expired: &is_expired }, 1_000u32);
+/// assert_eq!(clock.now().ticks(), 0);
+///
+/// is_expired.set(true);
+/// assert_eq!(clock.now().ticks(), 1);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "nb")))]
+#[cfg(feature = "nb")]
+pub struct Ticker<T, const HZ: u32> {
+    state: Mutex<RefCell<(T, u64)>>,
+    waker: Option<&'static AtomicWaker>,
+}
+
+#[cfg(feature = "nb")]
+impl<T: CountDown + Periodic, const HZ: u32> Ticker<T, HZ> {
+    /// Create a new clock at zero, starting `timer` with a `period` of one tick.
+    pub fn new(mut timer: T, period: impl Into<T::Time>) -> Self {
+        timer.start(period);
+        Self {
+            state: Mutex::new(RefCell::new((timer, 0))),
+            waker: None,
+        }
+    }
+
+    /// Create a new clock at zero like [`Ticker::new`] with a [`Monotonic::waker`],
+    /// which should be woken from the interrupt handler of the timer.
+    pub fn with_waker(timer: T, period: impl Into<T::Time>, waker: &'static AtomicWaker) -> Self {
+        Self {
+            waker: Some(waker),
+            ..Self::new(timer, period)
+        }
+    }
+}
+
+#[cfg(feature = "nb")]
+impl<T: CountDown + Periodic, const HZ: u32> Monotonic for Ticker<T, HZ> {
+    type Instant = Instant<HZ>;
+    type Duration = Duration<HZ>;
+
+    fn now(&self) -> Self::Instant {
+        critical_section::with(|cs| {
+            let (timer, ticks) = &mut *self.state.borrow_ref_mut(cs);
+            while timer.wait().is_ok() {
+                *ticks += 1;
+            }
+            Instant::from_ticks(*ticks)
+        })
+    }
+
+    fn waker(&self) -> Option<&AtomicWaker> {
+        self.waker
+    }
+}
+
+/// Mock [`Monotonic`] clock and [`Alarm`] that ticks at `HZ`, only advancing when told to.
+///
+/// Clones share the same time, so they can be moved into tasks
+/// and advanced from elsewhere, such as the `wait` callback of [`block_on`](crate::block_on).
+/// Advancing the clock wakes its [`Monotonic::waker`], like a tick interrupt.
+#[cfg_attr(docsrs, doc(cfg(feature = "mock")))]
+#[cfg(feature = "mock")]
+#[derive(Clone, Debug, Default)]
+pub struct MockClock<const HZ: u32> {
+    ticks: Arc<AtomicU64>,
+    alarm: Arc<StdMutex<Option<Instant<HZ>>>>,
+    waker: Arc<AtomicWaker>,
+}
+
+#[cfg(feature = "mock")]
+impl<const HZ: u32> MockClock<HZ> {
+    /// Create a new clock at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Move the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration<HZ>) {
+        self.ticks.fetch_add(duration.ticks(), Ordering::SeqCst);
+        self.waker.wake();
+    }
+
+    /// Move the clock to `instant`.
+    ///
+    /// # Panics
+    /// Panics if `instant` is before the current time.
+    pub fn set(&self, instant: Instant<HZ>) {
+        let previous = self.ticks.swap(instant.ticks(), Ordering::SeqCst);
+        assert!(
+            previous <= instant.ticks(),
+            "monotonic clock moved backwards"
+        );
+        self.waker.wake();
+    }
+
+    /// Returns the instant last programmed with [`Alarm::set_alarm`], if it's enabled.
+    pub fn next_alarm(&self) -> Option<Instant<HZ>> {
+        *self.alarm.lock().unwrap()
+    }
+}
+
+#[cfg(feature = "mock")]
+impl<const HZ: u32> Monotonic for MockClock<HZ> {
+    type Instant = Instant<HZ>;
+    type Duration = Duration<HZ>;
+
+    fn now(&self) -> Self::Instant {
+        Instant::from_ticks(self.ticks.load(Ordering::SeqCst))
+    }
+
+    fn waker(&self) -> Option<&AtomicWaker> {
+        Some(&self.waker)
+    }
+}
+
+#[cfg(feature = "mock")]
+impl<const HZ: u32> Alarm for MockClock<HZ> {
+    fn set_alarm(&self, instant: Option<Self::Instant>) {
+        *self.alarm.lock().unwrap() = instant;
+    }
+}
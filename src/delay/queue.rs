@@ -0,0 +1,317 @@
+use super::{DelayMs, Duration, Instant, Monotonic};
+use core::{
+    cell::Cell,
+    convert::Infallible,
+    marker::PhantomPinned,
+    pin::Pin,
+    ptr,
+    task::{Context, Poll, Waker},
+};
+use critical_section::{CriticalSection, Mutex};
+
+/// Compare channel of a hardware timer, used to drive a [`TimerQueue`].
+pub trait Alarm: Monotonic {
+    /// Program the compare channel to interrupt at `instant`, or disable it if `None`.
+    ///
+    /// Instants that have already passed may never interrupt,
+    /// so the queue checks the clock again after setting the alarm.
+    fn set_alarm(&self, instant: Option<Self::Instant>);
+}
+
+/// Queue of software timers sharing a single hardware [`Alarm`] that ticks at `HZ`.
+///
+/// Each [`QueueTimer`] is a [`DelayMs`] whose deadline is linked into a list sorted by deadline.
+/// The list is intrusive, so the nodes live inside the pinned timers and no allocation is needed.
+/// The alarm is reprogrammed for the earliest deadline whenever it changes,
+/// and [`TimerQueue::on_interrupt`] must be called from its interrupt handler to wake expired timers.
+/// ```
+/// use async_hal::delay::{Alarm, DelayMs, Duration, Instant, Monotonic, TimerQueue};
+/// use core::{cell::Cell, pin::pin};
+///
+/// struct Ticks {
+///     now: Cell<u64>,
+///     alarm: Cell<Option<Instant<1_000>>>,
+/// }
+///
+/// impl Monotonic for Ticks {
+///     type Instant = Instant<1_000>;
+///     type Duration = Duration<1_000>;
+///
+///     fn now(&self) -> Self::Instant {
+///         Instant::from_ticks(self.now.get())
+///     }
+/// }
+///
+/// impl Alarm for Ticks {
+///     fn set_alarm(&self, instant: Option<Self::Instant>) {
+///         self.alarm.set(instant);
+///     }
+/// }
+///
+/// let queue = TimerQueue::<_, 1_000>::new(Ticks {
+///     now: Cell::new(0),
+///     alarm: Cell::new(None),
+/// });
+/// let mut a = pin!(queue.timer());
+/// let mut b = pin!(queue.timer());
+///
+/// let task = futures::future::join(a.delay_ms(20), b.delay_ms(10));
+/// let (a, b) = async_hal::block_on(task, || {
+///     // Jump to the alarm and run its interrupt handler
+///     let ticks = queue.alarm();
+///     ticks.now.set(ticks.alarm.get().unwrap().ticks());
+///     queue.on_interrupt();
+/// });
+/// assert!(a.is_ok() && b.is_ok());
+/// assert_eq!(queue.alarm().now().ticks(), 20);
+/// ```
+pub struct TimerQueue<A, const HZ: u32> {
+    alarm: A,
+    head: Mutex<Cell<Link<HZ>>>,
+}
+
+impl<A, const HZ: u32> TimerQueue<A, HZ> {
+    /// Create a new empty queue driven by `alarm`.
+    pub const fn new(alarm: A) -> Self {
+        Self {
+            alarm,
+            head: Mutex::new(Cell::new(Link::NONE)),
+        }
+    }
+
+    /// Returns a reference to the alarm of this queue.
+    pub fn alarm(&self) -> &A {
+        &self.alarm
+    }
+}
+
+impl<A, const HZ: u32> TimerQueue<A, HZ>
+where
+    A: Alarm<Instant = Instant<HZ>, Duration = Duration<HZ>>,
+{
+    /// Create a new timer on this queue.
+    ///
+    /// Timers must be pinned to be polled, such as with [`core::pin::pin`].
+    pub fn timer(&self) -> QueueTimer<'_, A, HZ> {
+        QueueTimer {
+            queue: self,
+            node: Node {
+                deadline: Cell::new(None),
+                next: Cell::new(Link::NONE),
+                waker: Cell::new(None),
+                is_queued: Cell::new(false),
+                _pinned: PhantomPinned,
+            },
+        }
+    }
+
+    /// Wake every expired timer and program the alarm for the next deadline.
+    ///
+    /// This should be called from the interrupt handler of the alarm.
+    pub fn on_interrupt(&self) {
+        critical_section::with(|cs| self.expire(cs))
+    }
+
+    fn expire(&self, cs: CriticalSection) {
+        let head = self.head.borrow(cs);
+
+        loop {
+            let now = self.alarm.now();
+
+            // Safety: queued nodes are pinned and unlink themselves before they're dropped
+            while let Some(node) = unsafe { head.get().0.as_ref() } {
+                if node.deadline.get().is_some_and(|deadline| deadline > now) {
+                    break;
+                }
+
+                head.set(node.next.get());
+                node.is_queued.set(false);
+                if let Some(waker) = node.waker.take() {
+                    waker.wake();
+                }
+            }
+
+            // Safety: see above
+            let deadline = unsafe { head.get().0.as_ref() }.and_then(|node| node.deadline.get());
+            self.alarm.set_alarm(deadline);
+
+            // Expire again if the next deadline passed before the alarm was set
+            match deadline {
+                Some(deadline) if self.alarm.now() >= deadline => {}
+                _ => break,
+            }
+        }
+    }
+
+    fn insert(&self, cs: CriticalSection, node: &Node<HZ>) {
+        let head = self.head.borrow(cs);
+
+        // Find the first node with a later deadline, keeping timers with equal deadlines in order
+        let mut prev = head;
+        // Safety: queued nodes are pinned and unlink themselves before they're dropped
+        while let Some(next) = unsafe { prev.get().0.as_ref() } {
+            if next.deadline.get() > node.deadline.get() {
+                break;
+            }
+            prev = &next.next;
+        }
+
+        node.next.set(prev.get());
+        node.is_queued.set(true);
+        prev.set(Link(node));
+
+        if ptr::eq(prev, head) {
+            self.expire(cs);
+        }
+    }
+
+    fn remove(&self, cs: CriticalSection, node: &Node<HZ>) {
+        if self.unlink(cs, node) {
+            self.expire(cs);
+        }
+    }
+
+    /// Unlink `node` if it's queued, returning `true` if it was the head of the queue.
+    fn unlink(&self, cs: CriticalSection, node: &Node<HZ>) -> bool {
+        if !node.is_queued.replace(false) {
+            return false;
+        }
+
+        let head = self.head.borrow(cs);
+        let mut prev = head;
+        while !ptr::eq(prev.get().0, node) {
+            // Safety: queued nodes are pinned and unlink themselves before they're dropped,
+            // and `node` is queued so it's reached before the end of the list
+            prev = unsafe { &(*prev.get().0).next };
+        }
+        prev.set(node.next.get());
+
+        ptr::eq(prev, head)
+    }
+}
+
+/// Link to the next node in a [`TimerQueue`].
+struct Link<const HZ: u32>(*const Node<HZ>);
+
+impl<const HZ: u32> Link<HZ> {
+    const NONE: Self = Self(ptr::null());
+}
+
+impl<const HZ: u32> Clone for Link<HZ> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<const HZ: u32> Copy for Link<HZ> {}
+
+// Safety: links are only followed in a critical section
+unsafe impl<const HZ: u32> Send for Link<HZ> {}
+
+/// Node of a [`QueueTimer`] in the intrusive list of a [`TimerQueue`].
+///
+/// Fields are only accessed in a critical section, since the interrupt handler can reach queued nodes.
+struct Node<const HZ: u32> {
+    deadline: Cell<Option<Instant<HZ>>>,
+    next: Cell<Link<HZ>>,
+    waker: Cell<Option<Waker>>,
+    is_queued: Cell<bool>,
+    _pinned: PhantomPinned,
+}
+
+/// Software timer on a [`TimerQueue`], created with [`TimerQueue::timer`].
+///
+/// This timer must be pinned before it's polled,
+/// and [`DelayMs`] is also implemented for `Pin<&mut QueueTimer>` to use [`DelayMs::delay_ms`].
+pub struct QueueTimer<'a, A, const HZ: u32>
+where
+    A: Alarm<Instant = Instant<HZ>, Duration = Duration<HZ>>,
+{
+    queue: &'a TimerQueue<A, HZ>,
+    node: Node<HZ>,
+}
+
+impl<A, const HZ: u32> DelayMs for QueueTimer<'_, A, HZ>
+where
+    A: Alarm<Instant = Instant<HZ>, Duration = Duration<HZ>>,
+{
+    type Delay = u32;
+    type Error = Infallible;
+
+    fn start(&mut self, ms: Self::Delay) -> Result<(), Self::Error> {
+        critical_section::with(|cs| {
+            self.queue.remove(cs, &self.node);
+
+            let deadline = self.queue.alarm.now() + Duration::millis(u64::from(ms));
+            self.node.deadline.set(Some(deadline));
+        });
+        Ok(())
+    }
+
+    fn poll_delay_ms(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        let Self { queue, node } = &*self;
+
+        critical_section::with(|cs| {
+            let Some(deadline) = node.deadline.get() else {
+                return Poll::Ready(Ok(()));
+            };
+
+            if queue.alarm.now() < deadline {
+                node.waker.set(Some(cx.waker().clone()));
+                // The node is pinned, so it stays valid until it unlinks itself on drop
+                if !node.is_queued.get() {
+                    queue.insert(cs, node);
+                }
+
+                // Inserting at the head can expire the node right away
+                if node.is_queued.get() {
+                    return Poll::Pending;
+                }
+            }
+
+            queue.remove(cs, node);
+            node.deadline.set(None);
+            node.waker.set(None);
+            Poll::Ready(Ok(()))
+        })
+    }
+
+    fn cancel(&mut self) -> Result<(), Self::Error> {
+        critical_section::with(|cs| {
+            self.queue.remove(cs, &self.node);
+            self.node.deadline.set(None);
+        });
+        Ok(())
+    }
+}
+
+impl<A, const HZ: u32> DelayMs for Pin<&mut QueueTimer<'_, A, HZ>>
+where
+    A: Alarm<Instant = Instant<HZ>, Duration = Duration<HZ>>,
+{
+    type Delay = u32;
+    type Error = Infallible;
+
+    fn start(&mut self, ms: Self::Delay) -> Result<(), Self::Error> {
+        // Safety: starting a timer doesn't move it
+        unsafe { self.as_mut().get_unchecked_mut() }.start(ms)
+    }
+
+    fn poll_delay_ms(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().as_mut().poll_delay_ms(cx)
+    }
+
+    fn cancel(&mut self) -> Result<(), Self::Error> {
+        // Safety: cancelling a timer doesn't move it
+        unsafe { self.as_mut().get_unchecked_mut() }.cancel()
+    }
+}
+
+impl<A, const HZ: u32> Drop for QueueTimer<'_, A, HZ>
+where
+    A: Alarm<Instant = Instant<HZ>, Duration = Duration<HZ>>,
+{
+    fn drop(&mut self) {
+        critical_section::with(|cs| self.queue.remove(cs, &self.node));
+    }
+}
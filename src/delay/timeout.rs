@@ -0,0 +1,212 @@
+use super::{DelayMs, DelayMsFuture, DelayUntil, Monotonic};
+use core::{
+    fmt,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures::{ready, sink, Future, Sink, SinkExt};
+use pin_project_lite::pin_project;
+
+/// Error returned when a future doesn't complete before its deadline.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Elapsed {
+    _priv: (),
+}
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("deadline has elapsed")
+    }
+}
+
+/// Error returned by a [`Timeout`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeoutError<E> {
+    /// The delay expired before the future completed.
+    Elapsed(Elapsed),
+    /// The delay failed.
+    Delay(E),
+}
+
+impl<E> From<Elapsed> for TimeoutError<E> {
+    fn from(elapsed: Elapsed) -> Self {
+        Self::Elapsed(elapsed)
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for TimeoutError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Elapsed(elapsed) => elapsed.fmt(f),
+            Self::Delay(error) => write!(f, "delay failed: {error}"),
+        }
+    }
+}
+
+/// Run `future` until it completes or a delay of `ms` milliseconds expires.
+///
+/// The delay is started when the returned future is first polled
+/// and cancelled when it's dropped.
+/// ```
+/// use async_hal::delay::{self, TimeoutError};
+/// use futures::future;
+///
+/// let mut delay = delay::ready::<u32>();
+///
+/// let output = async_hal::block_on(delay::timeout(&mut delay, 50, future::ready(1)), || {});
+/// assert!(matches!(output, Ok(1)));
+///
+/// let output = async_hal::block_on(delay::timeout(&mut delay, 50, future::pending::<()>()), || {});
+/// assert!(matches!(output, Err(TimeoutError::Elapsed(_))));
+/// ```
+pub fn timeout<D, F>(delay: &mut D, ms: D::Delay, future: F) -> Timeout<'_, D, F>
+where
+    D: DelayMs + Unpin,
+    F: Future,
+{
+    Timeout {
+        future,
+        delay: delay.delay_ms(ms),
+    }
+}
+
+pin_project! {
+    /// Future for [`timeout`].
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct Timeout<'a, D: DelayMs, F> {
+        #[pin]
+        future: F,
+        #[pin]
+        delay: DelayMsFuture<'a, D>,
+    }
+}
+
+impl<D, F> Future for Timeout<'_, D, F>
+where
+    D: DelayMs + Unpin,
+    D::Delay: Unpin,
+    F: Future,
+{
+    type Output = Result<F::Output, TimeoutError<D::Error>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+
+        if let Poll::Ready(output) = this.future.poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+
+        let error = match ready!(this.delay.poll(cx)) {
+            Ok(()) => TimeoutError::Elapsed(Elapsed { _priv: () }),
+            Err(error) => TimeoutError::Delay(error),
+        };
+        Poll::Ready(Err(error))
+    }
+}
+
+/// Run `future` until it completes or `clock` reaches `deadline`.
+///
+/// Unlike [`timeout`], the same deadline can be shared by a sequence of operations.
+/// ```
+/// use async_hal::delay::{self, Duration, Instant, Monotonic};
+/// use core::cell::Cell;
+/// use futures::{future, task::AtomicWaker};
+///
+/// struct Millis {
+///     ticks: Cell<u64>,
+///     waker: AtomicWaker,
+/// }
+///
+/// impl Monotonic for Millis {
+///     type Instant = Instant<1_000>;
+///     type Duration = Duration<1_000>;
+///
+///     fn now(&self) -> Self::Instant {
+///         Instant::from_ticks(self.ticks.get())
+///     }
+///
+///     fn waker(&self) -> Option<&AtomicWaker> {
+///         Some(&self.waker)
+///     }
+/// }
+///
+/// let clock = Millis {
+///     ticks: Cell::new(0),
+///     waker: AtomicWaker::new(),
+/// };
+/// let deadline = clock.now() + Duration::millis(50);
+///
+/// let task = async {
+///     let first = delay::with_deadline(&clock, deadline, future::ready(1)).await;
+///     let second = delay::with_deadline(&clock, deadline, future::pending::<()>()).await;
+///     (first, second)
+/// };
+/// let (first, second) = async_hal::block_on(task, || {
+///     clock.ticks.set(clock.ticks.get() + 10);
+///     clock.waker.wake();
+/// });
+///
+/// assert_eq!(first, Ok(1));
+/// assert!(second.is_err());
+/// assert_eq!(clock.ticks.get(), 50);
+/// ```
+pub fn with_deadline<M, F>(clock: &M, deadline: M::Instant, future: F) -> WithDeadline<'_, M, F>
+where
+    M: Monotonic,
+    F: Future,
+{
+    WithDeadline {
+        future,
+        delay: clock.delay_until(deadline),
+    }
+}
+
+pin_project! {
+    /// Future for [`with_deadline`].
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct WithDeadline<'a, M: Monotonic, F> {
+        #[pin]
+        future: F,
+        #[pin]
+        delay: DelayUntil<'a, M>,
+    }
+}
+
+impl<M, F> Future for WithDeadline<'_, M, F>
+where
+    M: Monotonic,
+    F: Future,
+{
+    type Output = Result<F::Output, Elapsed>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.project();
+
+        if let Poll::Ready(output) = this.future.poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+
+        ready!(this.delay.poll(cx));
+        Poll::Ready(Err(Elapsed { _priv: () }))
+    }
+}
+
+/// Extension methods for sending to a [`Sink`] with a timeout.
+pub trait SinkTimeoutExt<Item>: Sink<Item> {
+    /// Send `item` into the sink, giving up if it isn't flushed before a delay of `ms` milliseconds expires.
+    /// See [`timeout`].
+    fn send_timeout<'a, D>(
+        &'a mut self,
+        item: Item,
+        delay: &'a mut D,
+        ms: D::Delay,
+    ) -> Timeout<'a, D, sink::Send<'a, Self, Item>>
+    where
+        Self: Unpin,
+        D: DelayMs + Unpin,
+    {
+        timeout(delay, ms, self.send(item))
+    }
+}
+
+impl<T, Item> SinkTimeoutExt<Item> for T where T: ?Sized + Sink<Item> {}
@@ -1,20 +1,52 @@
 use super::DelayMs;
+use crate::binding::{Binding, Driver};
 use core::{
     pin::Pin,
     task::{Context, Poll},
 };
 use embedded_hal::timer::{Cancel, CountDown};
 use fugit::MillisDurationU32;
+use futures::task::AtomicWaker;
 
 /// Async wrapper for a non-blocking countdown timer.
+///
+/// Timers created with [`Timer::with_waker`] or [`Timer::bind`] register the waker of the task that polls them,
+/// so the interrupt handler of the counter can wake it when the countdown expires.
+/// The handler must also stop the interrupt from firing again until the task has been polled,
+/// for example by masking it, since the expired flag is only cleared by [`CountDown::wait`].
+/// Timers created with [`Timer::new`] don't register a waker,
+/// so they rely on something else polling the task again, such as an executor run by the same interrupt.
 pub struct Timer<T> {
     counter: T,
+    waker: Option<&'static AtomicWaker>,
 }
 
 impl<T> Timer<T> {
     /// Create a new timer from `counter`.
     pub const fn new(counter: T) -> Self {
-        Self { counter }
+        Self {
+            counter,
+            waker: None,
+        }
+    }
+
+    /// Create a new timer from `counter` that registers with `waker`,
+    /// which should be woken from the interrupt handler of the counter.
+    pub const fn with_waker(counter: T, waker: &'static AtomicWaker) -> Self {
+        Self {
+            counter,
+            waker: Some(waker),
+        }
+    }
+
+    /// Create a new timer from `counter`, woken by the [`Driver::Interrupt`] of `T` bound in `irqs`.
+    /// See [`bind_interrupts!`](crate::bind_interrupts).
+    pub fn bind<B>(counter: T, _irqs: B) -> Self
+    where
+        T: Driver,
+        B: Binding<T::Interrupt, T>,
+    {
+        Self::with_waker(counter, B::waker())
     }
 }
 
@@ -35,7 +67,12 @@ where
         self.counter.cancel()
     }
 
-    fn poll_delay_ms(mut self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+    fn poll_delay_ms(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        // Register before checking the counter so an interrupt in between still wakes this task
+        if let Some(waker) = self.waker {
+            waker.register(cx.waker());
+        }
+
         match self.counter.wait() {
             Ok(()) => Poll::Ready(Ok(())),
             Err(nb::Error::Other(_void)) => unreachable!(),
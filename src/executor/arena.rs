@@ -0,0 +1,301 @@
+use super::{Interrupt, NonPending};
+use core::{
+    cell::UnsafeCell,
+    mem,
+    pin::Pin,
+    ptr,
+    sync::atomic::{AtomicBool, AtomicPtr, AtomicU8, Ordering},
+    task::{Context, Poll, RawWaker, RawWakerVTable, Waker},
+};
+use futures::Future;
+
+#[cfg(feature = "stats")]
+use crate::stats::{Stats, TaskStats};
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+enum Task {
+    Static(Pin<&'static mut dyn Future<Output = ()>>),
+    #[cfg(feature = "alloc")]
+    Boxed(Pin<Box<dyn Future<Output = ()>>>),
+}
+
+impl Task {
+    fn as_mut(&mut self) -> Pin<&mut dyn Future<Output = ()>> {
+        match self {
+            Self::Static(future) => future.as_mut(),
+            #[cfg(feature = "alloc")]
+            Self::Boxed(future) => future.as_mut(),
+        }
+    }
+}
+
+/// The slot holds no task.
+const FREE: u8 = 0;
+
+/// A task is being moved into the slot.
+const SPAWNING: u8 = 1;
+
+/// The slot holds a task that isn't being polled.
+const IDLE: u8 = 2;
+
+/// The slot's task is being polled.
+const POLLING: u8 = 3;
+
+struct Slot {
+    woken: AtomicBool,
+    /// Interrupt of the arena, type-erased so wakers can pend it without referencing the arena.
+    interrupt: AtomicPtr<()>,
+    /// Owner of `task`, which is only accessed by whoever moved the slot into `SPAWNING` or `POLLING`.
+    state: AtomicU8,
+    task: UnsafeCell<Option<Task>>,
+    #[cfg(feature = "stats")]
+    stats: Stats,
+}
+
+impl Slot {
+    const fn new() -> Self {
+        Self {
+            woken: AtomicBool::new(false),
+            interrupt: AtomicPtr::new(ptr::null_mut()),
+            state: AtomicU8::new(FREE),
+            task: UnsafeCell::new(None),
+            #[cfg(feature = "stats")]
+            stats: Stats::new(),
+        }
+    }
+}
+
+/// Frees a slot if its task panics while it's being polled, so the slot can be reused.
+struct FreeOnUnwind<'a> {
+    slot: &'a Slot,
+}
+
+impl Drop for FreeOnUnwind<'_> {
+    fn drop(&mut self) {
+        // Safety: the slot is still marked as polling, which gives exclusive access to its task
+        unsafe { *self.slot.task.get() = None };
+        self.slot.state.store(FREE, Ordering::Release);
+    }
+}
+
+/// Task executor for up to `N` heterogeneous `'static` futures.
+///
+/// Each task is stored in its own fixed slot and is given its own [`Waker`].
+/// Calling [`Arena::poll`] only polls the tasks whose wakers fired since the last poll.
+/// Waking any task will also pend the provided interrupt with [`Interrupt::pend`].
+///
+/// Arenas must be static for use with the waker and [`Pin`] support.
+/// ```
+/// use async_hal::executor::{Arena, NonPending};
+///
+/// static mut ARENA: Arena<NonPending, 4> = Arena::non_pending();
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "executor")))]
+pub struct Arena<I, const N: usize> {
+    interrupt: I,
+    slots: [Slot; N],
+}
+
+impl<I, const N: usize> Arena<I, N> {
+    /// Create a new empty arena.
+    pub const fn new(interrupt: I) -> Self {
+        Self {
+            interrupt,
+            slots: [const { Slot::new() }; N],
+        }
+    }
+
+    /// Returns a reference to the interrupt this arena pends.
+    pub fn interrupt(&self) -> &I {
+        &self.interrupt
+    }
+
+    /// Returns the number of tasks currently stored in the arena.
+    pub fn len(&self) -> usize {
+        self.slots
+            .iter()
+            .filter(|slot| slot.state.load(Ordering::Acquire) != FREE)
+            .count()
+    }
+
+    /// Returns `true` if the arena contains no tasks.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the stats of each slot, recorded since its last task was spawned.
+    #[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> [TaskStats; N] {
+        core::array::from_fn(|index| self.slots[index].stats.get())
+    }
+}
+
+impl<I: Interrupt, const N: usize> Arena<I, N> {
+    /// Spawn a [`Future`] into the first free slot of the arena and pend the interrupt.
+    /// This method returns Ok(()) if a slot was free and Err(future) if the arena was full.
+    ///
+    /// Slots are claimed atomically, so tasks can be spawned while the arena is being polled,
+    /// including from its own tasks.
+    pub fn spawn<F>(&self, future: &'static mut F) -> Result<(), &'static mut F>
+    where
+        F: Future<Output = ()>,
+    {
+        let Some(slot) = self.claim() else {
+            return Err(future);
+        };
+
+        // Safety: `future` is borrowed for `'static` so it can never be moved
+        self.fill(slot, Task::Static(unsafe { Pin::new_unchecked(future) }));
+        Ok(())
+    }
+
+    /// Spawn a [`Future`] into the first free slot of the arena like [`Arena::spawn`],
+    /// moving it to the heap.
+    ///
+    /// The future is only boxed once a slot is found, and it's freed when it completes.
+    /// This method returns Ok(()) if a slot was free and Err(future) if the arena was full.
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    pub fn spawn_boxed<F>(&self, future: F) -> Result<(), F>
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        let Some(slot) = self.claim() else {
+            return Err(future);
+        };
+
+        self.fill(slot, Task::Boxed(Box::pin(future)));
+        Ok(())
+    }
+
+    /// Claim the first free slot for a new task.
+    fn claim(&self) -> Option<&Slot> {
+        self.slots.iter().find(|slot| {
+            slot.state
+                .compare_exchange(FREE, SPAWNING, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+        })
+    }
+
+    /// Move `task` into a slot returned by [`Arena::claim`] and pend the interrupt to poll it.
+    fn fill(&self, slot: &Slot, task: Task) {
+        // Safety: claiming the slot gives exclusive access to its task
+        unsafe { *slot.task.get() = Some(task) };
+
+        #[cfg(feature = "stats")]
+        slot.stats.reset();
+
+        slot.state.store(IDLE, Ordering::Release);
+
+        // Mark the new task as woken so it's polled on the next interrupt
+        slot.woken.store(true, Ordering::Release);
+        self.interrupt.pend();
+    }
+}
+
+impl<I, const N: usize> Arena<I, N>
+where
+    I: Interrupt + Sync,
+{
+    /// Poll every task on the arena that has been woken since the last poll.
+    ///
+    /// Tasks that complete are removed from their slot,
+    /// as are tasks that panic, so the slot can be reused if the panic is caught.
+    /// This method returns `Poll::Ready(())` once the arena is empty.
+    pub fn poll(&'static self) -> Poll<()> {
+        for slot in &self.slots {
+            slot.interrupt
+                .store(&self.interrupt as *const I as *mut (), Ordering::Release);
+
+            if !slot.woken.swap(false, Ordering::AcqRel) {
+                continue;
+            }
+
+            match slot
+                .state
+                .compare_exchange(IDLE, POLLING, Ordering::AcqRel, Ordering::Acquire)
+            {
+                Ok(_) => {}
+                Err(POLLING) => {
+                    // This slot is already being polled further up the stack
+                    slot.woken.store(true, Ordering::Release);
+                    continue;
+                }
+                // Tasks being spawned mark themselves as woken once they're ready
+                Err(_) => continue,
+            }
+
+            let unwind = FreeOnUnwind { slot };
+
+            // Safety: marking the slot as polling gives exclusive access to its task
+            let task = unsafe { &mut *slot.task.get() };
+            if let Some(future) = task.as_mut() {
+                let raw_waker = RawWaker::new(slot as *const Slot as *const (), &Self::VTABLE);
+                // Safety: `slot` is static and the vtable upholds the `RawWaker` contract
+                let waker = unsafe { Waker::from_raw(raw_waker) };
+                let mut cx = Context::from_waker(&waker);
+
+                #[cfg(feature = "stats")]
+                let poll = slot
+                    .stats
+                    .measure(|| crate::task::poll(future.as_mut(), &mut cx));
+                #[cfg(not(feature = "stats"))]
+                let poll = crate::task::poll(future.as_mut(), &mut cx);
+
+                if poll.is_ready() {
+                    *task = None;
+                }
+            }
+
+            mem::forget(unwind);
+
+            let state = if task.is_some() { IDLE } else { FREE };
+            slot.state.store(state, Ordering::Release);
+        }
+
+        if self.is_empty() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+
+    const VTABLE: RawWakerVTable =
+        RawWakerVTable::new(Self::clone_waker, Self::wake, Self::wake, |_| {});
+
+    /// # Safety
+    /// `ptr` must have been created from a pointer to a static slot in [`Arena::poll`].
+    unsafe fn clone_waker(ptr: *const ()) -> RawWaker {
+        RawWaker::new(ptr, &Self::VTABLE)
+    }
+
+    /// # Safety
+    /// `ptr` must have been created from a pointer to a static slot in [`Arena::poll`].
+    unsafe fn wake(ptr: *const ()) {
+        let slot = unsafe { &*ptr.cast::<Slot>() };
+        slot.woken.store(true, Ordering::Release);
+
+        #[cfg(feature = "stats")]
+        slot.stats.record_wake();
+
+        // Safety: `poll` stores a pointer to the static interrupt before creating any wakers,
+        // and only the interrupt is shared since it's `Sync`
+        let interrupt = unsafe { &*slot.interrupt.load(Ordering::Acquire).cast::<I>() };
+        interrupt.pend();
+    }
+}
+
+impl<const N: usize> Arena<NonPending, N> {
+    pub const fn non_pending() -> Self {
+        Self::new(NonPending)
+    }
+}
+
+impl<I: Interrupt, const N: usize> Interrupt for Arena<I, N> {
+    fn pend(&self) {
+        self.interrupt.pend()
+    }
+}
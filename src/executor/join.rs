@@ -0,0 +1,42 @@
+use super::{Executor, Stage};
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures::Future;
+
+/// Future for the output of the task spawned on an [`Executor`].
+///
+/// Created by the [`Executor::join`] method.
+///
+/// # Panics
+///
+/// This future will panic if the output was already taken.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Join<'a, I, F: Future> {
+    executor: &'a Executor<I, F>,
+}
+
+impl<'a, I, F: Future> Join<'a, I, F> {
+    pub(super) fn new(executor: &'a Executor<I, F>) -> Self {
+        Self { executor }
+    }
+}
+
+impl<I, F: Future> Future for Join<'_, I, F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        // Register first so a task that finishes on another interrupt can't be missed
+        self.executor.join_waker.register(cx.waker());
+
+        if let Some(output) = self.executor.take_output() {
+            return Poll::Ready(output);
+        }
+
+        match self.executor.stage.try_borrow().as_deref() {
+            Ok(Stage::Consumed) => panic!("`Join` polled after the output was taken"),
+            _ => Poll::Pending,
+        }
+    }
+}
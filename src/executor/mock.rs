@@ -0,0 +1,294 @@
+use super::{Interrupt, SetPriority};
+use core::{
+    cell::RefCell,
+    convert::Infallible,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use std::{
+    rc::Rc,
+    sync::Mutex,
+    thread::{self, ThreadId},
+};
+
+/// Record of an interrupt line being pended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pend {
+    /// Name of the pended line.
+    pub line: &'static str,
+    /// Name of the line whose handler was running at the time,
+    /// or `None` if it was pended from thread mode.
+    pub from: Option<&'static str>,
+}
+
+struct Line {
+    name: &'static str,
+    priority: u8,
+    is_masked: bool,
+    is_pending: bool,
+    fired: usize,
+    waker: Option<Waker>,
+}
+
+#[derive(Default)]
+struct State {
+    lines: Vec<Line>,
+    active: Vec<usize>,
+    pends: Vec<Pend>,
+}
+
+/// Simulated nested vectored interrupt controller.
+///
+/// Each [`MockLine`] has a priority, where higher values preempt lower ones like [`Level`](super::Level).
+/// Pending a line runs its handler immediately if it's unmasked and more urgent than the running handler,
+/// otherwise it stays pending until the line is unmasked or the running handler returns.
+///
+/// Handlers run on the thread that created the controller, which acts as the simulated core.
+/// Lines can be pended (for example by wakers) from other threads,
+/// but their handlers only run once the core calls [`MockNvic::dispatch`] or pends a line itself.
+/// ```
+/// use async_hal::executor::{Arena, MockNvic, Pend};
+///
+/// let nvic = MockNvic::new();
+/// let arena: &'static Arena<_, 1> = Box::leak(Box::new(Arena::new(nvic.line("uart", 1))));
+/// nvic.set_handler(arena.interrupt(), move || _ = arena.poll());
+///
+/// arena.spawn(Box::leak(Box::new(async {}))).ok().unwrap();
+/// assert!(arena.is_empty());
+/// assert_eq!(nvic.take_pends(), [Pend { line: "uart", from: None }]);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(all(feature = "executor", feature = "mock"))))]
+pub struct MockNvic {
+    state: Mutex<State>,
+    handlers: Handlers,
+}
+
+impl MockNvic {
+    /// Create a new controller with no lines, owned by the current thread.
+    pub fn new() -> &'static Self {
+        Box::leak(Box::new(Self {
+            state: Mutex::default(),
+            handlers: Handlers {
+                owner: thread::current().id(),
+                handlers: RefCell::default(),
+            },
+        }))
+    }
+
+    /// Add a new unmasked line called `name` with the given `priority`.
+    pub fn line(&'static self, name: &'static str, priority: u8) -> MockLine {
+        let mut state = self.state.lock().unwrap();
+        let line = state.lines.len();
+        state.lines.push(Line {
+            name,
+            priority,
+            is_masked: false,
+            is_pending: false,
+            fired: 0,
+            waker: None,
+        });
+        drop(state);
+
+        self.handlers.get().borrow_mut().push(None);
+
+        MockLine {
+            nvic: self,
+            line,
+            seen: 0,
+        }
+    }
+
+    /// Run `handler` every time `line` fires, usually to poll its executor.
+    pub fn set_handler(&self, line: &MockLine, handler: impl Fn() + 'static) {
+        self.handlers.get().borrow_mut()[line.line] = Some(Rc::new(handler));
+    }
+
+    /// Returns the name of the line whose handler is currently running.
+    pub fn active(&self) -> Option<&'static str> {
+        let state = self.state.lock().unwrap();
+        state.active.last().map(|&line| state.lines[line].name)
+    }
+
+    /// Take the record of every line pended so far.
+    pub fn take_pends(&self) -> Vec<Pend> {
+        core::mem::take(&mut self.state.lock().unwrap().pends)
+    }
+
+    /// Run the handlers of pending lines that can preempt the current context.
+    ///
+    /// This does nothing when called from a thread other than the simulated core.
+    pub fn dispatch(&self) {
+        if thread::current().id() != self.handlers.owner {
+            return;
+        }
+
+        loop {
+            let mut state = self.state.lock().unwrap();
+            let running = state.active.last().map(|&line| state.lines[line].priority);
+            let next = state
+                .lines
+                .iter()
+                .enumerate()
+                .filter(|(_, line)| line.is_pending && !line.is_masked)
+                // `None` orders before any priority, so every line preempts thread mode
+                .filter(|(_, line)| Some(line.priority) > running)
+                // Lower line numbers win ties, like on Cortex-M
+                .max_by_key(|(index, line)| (line.priority, usize::MAX - index))
+                .map(|(index, _)| index);
+            let Some(index) = next else {
+                break;
+            };
+
+            let line = &mut state.lines[index];
+            line.is_pending = false;
+            line.fired += 1;
+            let waker = line.waker.take();
+            state.active.push(index);
+            drop(state);
+
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+
+            let handler = self.handlers.get().borrow()[index].clone();
+            if let Some(handler) = handler {
+                handler();
+            }
+
+            self.state.lock().unwrap().active.pop();
+        }
+    }
+
+    fn pend(&self, index: usize) {
+        let mut state = self.state.lock().unwrap();
+        let from = if thread::current().id() == self.handlers.owner {
+            state.active.last().map(|&line| state.lines[line].name)
+        } else {
+            None
+        };
+
+        let line = &mut state.lines[index];
+        line.is_pending = true;
+        let pend = Pend {
+            line: line.name,
+            from,
+        };
+        state.pends.push(pend);
+        drop(state);
+
+        self.dispatch();
+    }
+
+    fn set_masked(&self, index: usize, is_masked: bool) {
+        self.state.lock().unwrap().lines[index].is_masked = is_masked;
+        self.dispatch();
+    }
+}
+
+type Handler = Rc<dyn Fn()>;
+
+/// Handlers that can only be accessed from the simulated core.
+struct Handlers {
+    owner: ThreadId,
+    handlers: RefCell<Vec<Option<Handler>>>,
+}
+
+// Safety: the handlers are only accessed through `get`, which checks they're on their owner thread
+unsafe impl Send for Handlers {}
+unsafe impl Sync for Handlers {}
+
+impl Handlers {
+    fn get(&self) -> &RefCell<Vec<Option<Handler>>> {
+        assert_eq!(
+            thread::current().id(),
+            self.owner,
+            "interrupt handlers can only be used on the thread that created the controller"
+        );
+        &self.handlers
+    }
+}
+
+/// Interrupt line of a [`MockNvic`].
+///
+/// Lines can be pended with [`Interrupt::pend`], so executors using them
+/// run their handler when a task is woken.
+/// They also implement [`crate::Interrupt`], yielding an item every time the line fires.
+#[derive(Clone)]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "executor", feature = "mock"))))]
+pub struct MockLine {
+    nvic: &'static MockNvic,
+    line: usize,
+    seen: usize,
+}
+
+impl MockLine {
+    /// Returns the name of this line.
+    pub fn name(&self) -> &'static str {
+        self.nvic.state.lock().unwrap().lines[self.line].name
+    }
+
+    /// Returns the priority of this line.
+    pub fn priority(&self) -> u8 {
+        self.nvic.state.lock().unwrap().lines[self.line].priority
+    }
+
+    /// Returns `true` if this line is waiting for its handler to run.
+    pub fn is_pending(&self) -> bool {
+        self.nvic.state.lock().unwrap().lines[self.line].is_pending
+    }
+
+    /// Prevent this line's handler from running.
+    pub fn mask(&self) {
+        self.nvic.set_masked(self.line, true)
+    }
+
+    /// Allow this line's handler to run, running it now if the line is pending.
+    pub fn unmask(&self) {
+        self.nvic.set_masked(self.line, false)
+    }
+}
+
+impl Interrupt for MockLine {
+    fn pend(&self) {
+        self.nvic.pend(self.line)
+    }
+}
+
+impl SetPriority for MockLine {
+    unsafe fn set_priority(&self, priority: u8) {
+        self.nvic.state.lock().unwrap().lines[self.line].priority = priority;
+    }
+}
+
+impl crate::Interrupt for MockLine {
+    type Error = Infallible;
+
+    /// Unmask the line, ignoring any times it fired before.
+    fn enable(&mut self) -> Result<(), Self::Error> {
+        self.seen = self.nvic.state.lock().unwrap().lines[self.line].fired;
+        self.unmask();
+        Ok(())
+    }
+
+    fn disable(&mut self) -> Result<(), Self::Error> {
+        self.mask();
+        Ok(())
+    }
+
+    fn poll_interrupt(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        let nvic = self.nvic;
+        let mut state = nvic.state.lock().unwrap();
+        let line = &mut state.lines[self.line];
+
+        if line.fired > self.seen {
+            let fired = line.fired;
+            drop(state);
+
+            self.seen = fired;
+            Poll::Ready(Ok(()))
+        } else {
+            line.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
@@ -0,0 +1,310 @@
+use core::{
+    cell::{Cell, RefCell},
+    pin::Pin,
+    task::{Context, Poll},
+};
+use critical_section::{CriticalSection, Mutex};
+use futures::{ready, task::AtomicWaker, Future};
+
+#[cfg(feature = "stats")]
+use crate::stats::{Stats, TaskStats};
+
+mod arena;
+pub use arena::Arena;
+
+mod join;
+pub use join::Join;
+
+#[cfg(feature = "mock")]
+mod mock;
+#[cfg(feature = "mock")]
+pub use mock::{MockLine, MockNvic, Pend};
+
+pub mod pend;
+#[cfg(feature = "cortex-m")]
+pub use pend::Nvic;
+pub use pend::{pend_fn, EventFlag, PendCounter, PendFn};
+
+mod supervisor;
+pub use supervisor::Supervisor;
+
+mod waker;
+pub use waker::waker;
+
+mod scoped;
+pub use scoped::ScopedExecutor;
+
+pub mod priority;
+pub use priority::{Level, SetPriority};
+
+mod spawner;
+pub use spawner::Spawner;
+
+mod static_executor;
+pub use static_executor::StaticExecutor;
+
+pub mod storage;
+pub use storage::TaskStorage;
+
+pub trait Interrupt {
+    /// Pend this interrupt handler to run.
+    fn pend(&self);
+}
+
+pub struct NonPending;
+
+impl Interrupt for NonPending {
+    fn pend(&self) {}
+}
+
+/// Error returned when a task can't be spawned
+/// because its executor or storage is already in use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpawnError;
+
+/// Task executor for a single `'static` future.
+///
+/// This provides a polling interface for a [`Future`] running on an interrupt handler.
+/// If the provided [`Waker`](core::task::Waker) is woken, the executor will pend the provided interrupt with [`Interrupt::pend`].
+///
+/// Once the future completes it is dropped and never polled again.
+/// Its output is stored in the executor until it's taken with [`Executor::join`] or [`Executor::take_output`].
+///
+/// Executors must be static for use with the waker and [`Pin`] support.
+/// ```
+/// use async_hal::Executor;
+/// use core::future::Ready;
+///
+/// static mut EXECUTOR: Executor<(), Ready<()>> = Executor::new(());
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "executor")))]
+pub struct Executor<I, F: Future> {
+    source: Source<I>,
+    stage: RefCell<Stage<F>>,
+    restart: Mutex<Cell<Option<F>>>,
+    join_waker: AtomicWaker,
+}
+
+/// Interrupt pended by the waker of an [`Executor`],
+/// which also counts the wakes of its task with the `stats` feature.
+struct Source<I> {
+    interrupt: I,
+    #[cfg(feature = "stats")]
+    stats: Stats,
+}
+
+impl<I: Interrupt> Interrupt for Source<I> {
+    fn pend(&self) {
+        #[cfg(feature = "stats")]
+        self.stats.record_wake();
+
+        self.interrupt.pend()
+    }
+}
+
+enum Stage<F: Future> {
+    Empty,
+    Running(F),
+    Finished(F::Output),
+    Consumed,
+}
+
+impl<I, F: Future> Executor<I, F> {
+    /// Create a new empty executor.
+    pub const fn new(interrupt: I) -> Self {
+        Self {
+            source: Source {
+                interrupt,
+                #[cfg(feature = "stats")]
+                stats: Stats::new(),
+            },
+            stage: RefCell::new(Stage::Empty),
+            restart: Mutex::new(Cell::new(None)),
+            join_waker: AtomicWaker::new(),
+        }
+    }
+
+    /// Returns a reference to the interrupt this executor pends.
+    pub fn interrupt(&self) -> &I {
+        &self.source.interrupt
+    }
+
+    /// Returns the stats of the current task, recorded since it was spawned.
+    #[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> TaskStats {
+        self.source.stats.get()
+    }
+
+    /// Spawn a single [`Future`] on the executor.
+    /// This method returns Ok(()) if the executor was empty and Err(value) if it was full.
+    pub fn spawn(&self, future: F) -> Result<(), F> {
+        match self.stage.try_borrow_mut() {
+            Ok(mut stage) if matches!(*stage, Stage::Empty) => {
+                self.start(&mut stage, future);
+                Ok(())
+            }
+            _ => Err(future),
+        }
+    }
+
+    /// Cancel the current [`Future`] by dropping it in place, leaving the executor empty.
+    ///
+    /// Any output that hasn't been taken is also dropped.
+    /// This method returns `true` if a future or output was dropped
+    /// and `false` if the executor was already empty or is currently being polled.
+    pub fn cancel(&self) -> bool {
+        let Ok(mut stage) = self.stage.try_borrow_mut() else {
+            return false;
+        };
+
+        let was_empty = matches!(*stage, Stage::Empty);
+        *stage = Stage::Empty;
+        !was_empty
+    }
+
+    /// Replace the current [`Future`] with `future`, dropping the previous one in place,
+    /// and pend the interrupt to start polling it.
+    ///
+    /// If the executor is currently being polled, such as when called from the task it is running,
+    /// the restart is queued and the previous future is replaced at the start of the next poll.
+    /// Queueing another restart before then drops the queued future.
+    pub fn respawn(&self, future: F)
+    where
+        I: Interrupt,
+    {
+        match self.stage.try_borrow_mut() {
+            Ok(mut stage) => self.start(&mut stage, future),
+            Err(_) => critical_section::with(|cs| self.queue_restart(cs, future)),
+        }
+
+        self.source.interrupt.pend();
+    }
+
+    /// Queue `future` to replace the current [`Future`] at the start of the next poll.
+    pub(super) fn queue_restart(&self, cs: CriticalSection, future: F) {
+        self.restart.borrow(cs).set(Some(future));
+    }
+
+    fn start(&self, stage: &mut Stage<F>, future: F) {
+        *stage = Stage::Running(future);
+
+        #[cfg(feature = "stats")]
+        self.source.stats.reset();
+    }
+
+    /// Returns `true` if the spawned [`Future`] has completed.
+    pub fn is_finished(&self) -> bool {
+        self.stage
+            .try_borrow()
+            .is_ok_and(|stage| matches!(*stage, Stage::Finished(_) | Stage::Consumed))
+    }
+
+    /// Take the output of the completed [`Future`], if it hasn't been taken already.
+    pub fn take_output(&self) -> Option<F::Output> {
+        let mut stage = self.stage.try_borrow_mut().ok()?;
+        if !matches!(*stage, Stage::Finished(_)) {
+            return None;
+        }
+
+        match core::mem::replace(&mut *stage, Stage::Consumed) {
+            Stage::Finished(output) => Some(output),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Wait for the spawned [`Future`] to complete and return its output.
+    ///
+    /// The returned future can be awaited from another task, such as one running on a different executor.
+    /// ```
+    /// use async_hal::executor::{Executor, NonPending};
+    /// use core::future::{ready, Ready};
+    ///
+    /// let executor: &'static Executor<NonPending, Ready<u8>> =
+    ///     Box::leak(Box::new(Executor::non_pending()));
+    /// executor.spawn(ready(42)).unwrap();
+    /// assert!(executor.poll().is_ready());
+    ///
+    /// let output = async_hal::block_on(executor.join(), || {});
+    /// assert_eq!(output, 42);
+    /// ```
+    pub fn join(&self) -> Join<'_, I, F> {
+        Join::new(self)
+    }
+
+    /// Poll the current [`Future`] on the executor.
+    ///
+    /// This method returns `Poll::Ready(())` once the future has completed,
+    /// without polling it again, and `Poll::Pending` if the executor is empty.
+    pub fn poll(&'static self) -> Poll<()>
+    where
+        I: Interrupt + Sync,
+    {
+        let waker = waker(&self.source);
+        let mut cx = Context::from_waker(&waker);
+
+        let mut stage = self.stage.borrow_mut();
+        if let Some(future) = critical_section::with(|cs| self.restart.borrow(cs).take()) {
+            self.start(&mut stage, future);
+        }
+
+        let future = match &mut *stage {
+            Stage::Empty => return Poll::Pending,
+            Stage::Running(future) => future,
+            Stage::Finished(_) | Stage::Consumed => return Poll::Ready(()),
+        };
+
+        // Safety: `future` is guranteed to be static
+        let pinned = unsafe { Pin::new_unchecked(future) };
+
+        #[cfg(feature = "stats")]
+        let poll = self
+            .source
+            .stats
+            .measure(|| crate::task::poll(pinned, &mut cx));
+        #[cfg(not(feature = "stats"))]
+        let poll = crate::task::poll(pinned, &mut cx);
+
+        let output = ready!(poll);
+
+        *stage = Stage::Finished(output);
+        drop(stage);
+
+        self.join_waker.wake();
+        Poll::Ready(())
+    }
+
+    /// Poll the current [`Future`] on the executor like [`Executor::poll`],
+    /// then call `idle` with the earliest pending deadline of `deadlines` if it's still pending.
+    ///
+    /// Interrupt handlers can use this to reprogram a low-power wakeup timer before returning.
+    /// See [`block_on_idle`](crate::block_on_idle).
+    #[cfg_attr(docsrs, doc(cfg(feature = "delay")))]
+    #[cfg(feature = "delay")]
+    pub fn poll_idle<M, const HZ: u32, const N: usize>(
+        &'static self,
+        deadlines: &crate::delay::Deadlines<M, HZ, N>,
+        idle: impl FnOnce(Option<crate::delay::Instant<HZ>>),
+    ) -> Poll<()>
+    where
+        I: Interrupt + Sync,
+    {
+        let poll = self.poll();
+        if poll.is_pending() {
+            idle(deadlines.next_deadline());
+        }
+        poll
+    }
+}
+
+impl<F: Future> Executor<NonPending, F> {
+    pub const fn non_pending() -> Self {
+        Self::new(NonPending)
+    }
+}
+
+impl<I: Interrupt, F: Future> Interrupt for Executor<I, F> {
+    fn pend(&self) {
+        self.source.interrupt.pend()
+    }
+}
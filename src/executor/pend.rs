@@ -0,0 +1,199 @@
+//! Ready-made [`Interrupt`] implementations.
+
+#[cfg(feature = "cortex-m")]
+use super::priority::SetPriority;
+use super::Interrupt;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Interrupt line of the Cortex-M NVIC, pended in software
+/// so waking a task re-runs the handler that polls its executor.
+///
+/// `PRIO_BITS` is the number of priority bits implemented by the device, usually `NVIC_PRIO_BITS` from its PAC.
+/// Logical priorities of a [`Level`](super::Level) are mapped onto them,
+/// with level `0` being the least urgent.
+/// ```ignore
+/// use async_hal::executor::{Arena, Nvic, StaticExecutor};
+/// use stm32f1xx_hal::pac::{interrupt, Interrupt};
+///
+/// static EXECUTOR: StaticExecutor<Arena<Nvic<Interrupt>, 4>> =
+///     StaticExecutor::arena(Nvic::new(Interrupt::TIM2));
+///
+/// #[interrupt]
+/// fn TIM2() {
+///     _ = EXECUTOR.poll();
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "cortex-m")))]
+#[cfg(feature = "cortex-m")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Nvic<T, const PRIO_BITS: u8 = 4> {
+    interrupt: T,
+}
+
+#[cfg(feature = "cortex-m")]
+impl<T, const PRIO_BITS: u8> Nvic<T, PRIO_BITS> {
+    /// Create a new handle to the line of `interrupt`.
+    pub const fn new(interrupt: T) -> Self {
+        Self { interrupt }
+    }
+
+    /// Returns the interrupt number of this line.
+    pub fn interrupt(&self) -> &T {
+        &self.interrupt
+    }
+}
+
+#[cfg(feature = "cortex-m")]
+impl<T: cortex_m::interrupt::InterruptNumber, const PRIO_BITS: u8> Interrupt
+    for Nvic<T, PRIO_BITS>
+{
+    fn pend(&self) {
+        cortex_m::peripheral::NVIC::pend(self.interrupt)
+    }
+}
+
+#[cfg(feature = "cortex-m")]
+impl<T: cortex_m::interrupt::InterruptNumber, const PRIO_BITS: u8> SetPriority
+    for Nvic<T, PRIO_BITS>
+{
+    /// Set the NVIC priority of this line, where lower hardware values are more urgent.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `priority` doesn't fit in `PRIO_BITS`.
+    unsafe fn set_priority(&self, priority: u8) {
+        let levels = 1u16 << PRIO_BITS;
+        assert!(u16::from(priority) < levels, "priority out of range");
+
+        let hardware = ((levels - 1 - u16::from(priority)) << (8 - PRIO_BITS)) as u8;
+        cortex_m::Peripherals::steal()
+            .NVIC
+            .set_priority(self.interrupt, hardware);
+    }
+}
+
+/// Flag for running an executor in thread mode, sleeping with `wfe` until it's pended.
+///
+/// Pending sets the flag and, on Cortex-M with the `cortex-m` feature, signals an event with `sev`
+/// so a `wfe` that races with the pend returns immediately.
+/// ```
+/// use async_hal::executor::{Arena, EventFlag, StaticExecutor};
+///
+/// static EXECUTOR: StaticExecutor<Arena<EventFlag, 4>> = StaticExecutor::arena(EventFlag::new());
+///
+/// EXECUTOR.spawn(Box::leak(Box::new(async {}))).ok().unwrap();
+/// while EXECUTOR.poll().is_pending() {
+///     // Sleep with `cortex_m::asm::wfe` until a task is woken
+///     EXECUTOR.interrupt().wait(|| {});
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct EventFlag {
+    is_set: AtomicBool,
+}
+
+impl EventFlag {
+    /// Create a new cleared flag.
+    pub const fn new() -> Self {
+        Self {
+            is_set: AtomicBool::new(false),
+        }
+    }
+
+    /// Returns `true` if the flag was pended since it was last taken.
+    pub fn is_set(&self) -> bool {
+        self.is_set.load(Ordering::Acquire)
+    }
+
+    /// Clear the flag, returning `true` if it was set.
+    pub fn take(&self) -> bool {
+        self.is_set.swap(false, Ordering::AcqRel)
+    }
+
+    /// Call `wait()` until the flag is set, then clear it.
+    pub fn wait(&self, mut wait: impl FnMut()) {
+        while !self.take() {
+            wait()
+        }
+    }
+}
+
+impl Interrupt for EventFlag {
+    fn pend(&self) {
+        self.is_set.store(true, Ordering::Release);
+
+        #[cfg(all(feature = "cortex-m", target_arch = "arm"))]
+        cortex_m::asm::sev();
+    }
+}
+
+/// Create an [`Interrupt`] that calls `f` when pended,
+/// such as to set a PendSV or trigger a software interrupt on another core.
+/// ```
+/// use async_hal::executor::{self, Interrupt};
+/// use core::sync::atomic::{AtomicBool, Ordering};
+///
+/// static IS_PENDED: AtomicBool = AtomicBool::new(false);
+///
+/// let interrupt = executor::pend_fn(|| IS_PENDED.store(true, Ordering::SeqCst));
+/// interrupt.pend();
+/// assert!(IS_PENDED.load(Ordering::SeqCst));
+/// ```
+pub const fn pend_fn<F: Fn()>(f: F) -> PendFn<F> {
+    PendFn { f }
+}
+
+/// Interrupt that calls a closure when pended.
+///
+/// Created by the [`pend_fn`] function.
+#[derive(Clone, Copy)]
+pub struct PendFn<F> {
+    f: F,
+}
+
+impl<F: Fn()> Interrupt for PendFn<F> {
+    fn pend(&self) {
+        (self.f)()
+    }
+}
+
+/// Interrupt that records how many times it was pended,
+/// for checking that wakes reach an executor in host tests.
+/// ```
+/// use async_hal::executor::{self, PendCounter};
+///
+/// static PENDS: PendCounter = PendCounter::new();
+///
+/// executor::waker(&PENDS).wake();
+/// assert_eq!(PENDS.take(), 1);
+/// assert_eq!(PENDS.count(), 0);
+/// ```
+#[derive(Debug, Default)]
+pub struct PendCounter {
+    count: AtomicUsize,
+}
+
+impl PendCounter {
+    /// Create a new counter at zero.
+    pub const fn new() -> Self {
+        Self {
+            count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of pends since the counter was last taken.
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Acquire)
+    }
+
+    /// Reset the counter, returning the number of pends since it was last taken.
+    pub fn take(&self) -> usize {
+        self.count.swap(0, Ordering::AcqRel)
+    }
+}
+
+impl Interrupt for PendCounter {
+    fn pend(&self) {
+        self.count.fetch_add(1, Ordering::AcqRel);
+    }
+}
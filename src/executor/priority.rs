@@ -0,0 +1,107 @@
+//! Priority-preemptive executors.
+//!
+//! Each priority level of an application runs its own [`Executor`](super::Executor) or
+//! [`Arena`](super::Arena), bound to a distinct interrupt whose hardware priority matches the level.
+//! Waking a task pends the interrupt of the executor it was spawned on,
+//! so when a task running at a low level wakes a task at a higher level the interrupt controller
+//! immediately preempts the low level, polls the higher executor, and then resumes the lower one.
+//!
+//! [`Level`] pairs an interrupt with its logical priority, where higher values preempt lower ones.
+//! Its priority is written to the interrupt controller with [`Level::configure`],
+//! which must be called for every level at startup, before any task is spawned.
+//! ```ignore
+//! use async_hal::executor::{Arena, Level, Nvic, StaticExecutor};
+//! use stm32f1xx_hal::pac::Interrupt;
+//!
+//! static CONTROL: StaticExecutor<Arena<Level<Nvic<Interrupt>>, 2>> =
+//!     StaticExecutor::arena(Level::new(Nvic::new(Interrupt::TIM2), 2));
+//! static LOGGING: StaticExecutor<Arena<Level<Nvic<Interrupt>>, 4>> =
+//!     StaticExecutor::arena(Level::new(Nvic::new(Interrupt::TIM3), 1));
+//!
+//! // Safety: no critical sections are based on these priorities
+//! unsafe {
+//!     CONTROL.interrupt().configure();
+//!     LOGGING.interrupt().configure();
+//! }
+//! ```
+//!
+//! ## Sharing data between levels
+//!
+//! A higher level can interrupt a lower level at any `.await` point *and* between any two
+//! instructions of a poll, so data shared between levels must never be accessed through a plain
+//! `RefCell` or `static mut`.
+//! Instead:
+//! - Use atomics for flags and counters.
+//! - Use single-producer single-consumer queues such as [`io::Queue`](crate::io::queue::Queue)
+//!   to pass data from one level to another. Their wakers pend the consumer's interrupt.
+//! - Use a critical section for anything larger, keeping it as short as possible
+//!   because it blocks every level, including ones that don't share the data.
+//!
+//! Data that is only used by a single level can be owned by a task on that level's executor
+//! and needs no synchronization at all.
+
+use super::Interrupt;
+
+/// An [`Interrupt`] assigned to a logical priority level.
+///
+/// Higher levels preempt lower levels.
+#[cfg_attr(docsrs, doc(cfg(feature = "executor")))]
+pub struct Level<I> {
+    interrupt: I,
+    priority: u8,
+}
+
+impl<I> Level<I> {
+    /// Assign `interrupt` to the logical `priority` level.
+    pub const fn new(interrupt: I, priority: u8) -> Self {
+        Self {
+            interrupt,
+            priority,
+        }
+    }
+
+    /// Returns the logical priority of this level.
+    pub const fn priority(&self) -> u8 {
+        self.priority
+    }
+
+    /// Returns `true` if pending this level would preempt a handler running at `other`.
+    pub const fn preempts<J>(&self, other: &Level<J>) -> bool {
+        self.priority > other.priority
+    }
+
+    /// Returns a reference to the underlying interrupt.
+    pub const fn get_ref(&self) -> &I {
+        &self.interrupt
+    }
+
+    /// Write the priority of this level to the interrupt controller.
+    ///
+    /// # Safety
+    ///
+    /// See [`SetPriority::set_priority`].
+    pub unsafe fn configure(&self)
+    where
+        I: SetPriority,
+    {
+        self.interrupt.set_priority(self.priority)
+    }
+}
+
+impl<I: Interrupt> Interrupt for Level<I> {
+    fn pend(&self) {
+        self.interrupt.pend()
+    }
+}
+
+/// [`Interrupt`] whose priority can be set in the interrupt controller.
+#[cfg_attr(docsrs, doc(cfg(feature = "executor")))]
+pub trait SetPriority: Interrupt {
+    /// Set the logical `priority` of this interrupt, where higher values preempt lower ones.
+    ///
+    /// # Safety
+    ///
+    /// Changing the priority of an interrupt can break critical sections
+    /// that rely on it not preempting the running code, such as priority-based locks.
+    unsafe fn set_priority(&self, priority: u8);
+}
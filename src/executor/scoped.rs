@@ -0,0 +1,122 @@
+use super::{waker, Interrupt, NonPending};
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures::Future;
+
+type Task<'a> = Pin<&'a mut (dyn Future<Output = ()> + 'a)>;
+
+/// Task executor for up to `N` futures borrowed for the lifetime `'a`.
+///
+/// Unlike [`Executor`](super::Executor) and [`Arena`](super::Arena), neither the executor nor its futures need to be `'static`,
+/// so tasks can borrow local variables, such as a driver under test and the results it produces.
+/// Only the interrupt has to be `'static`, since wakers can outlive the executor.
+/// Waking any task pends the interrupt with [`Interrupt::pend`], and every task is polled on each [`ScopedExecutor::poll`].
+/// ```
+/// use async_hal::executor::ScopedExecutor;
+/// use core::pin::pin;
+///
+/// let mut log = Vec::new();
+/// let mut total = 0;
+///
+/// let mut executor = ScopedExecutor::<_, 2>::non_pending();
+/// let a = pin!(async { log.push("a") });
+/// let b = pin!(async { total += 1 });
+/// executor.spawn(a).ok().unwrap();
+/// executor.spawn(b).ok().unwrap();
+///
+/// executor.run(|| {});
+///
+/// assert_eq!(log, ["a"]);
+/// assert_eq!(total, 1);
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "executor")))]
+pub struct ScopedExecutor<'a, I: 'static, const N: usize> {
+    interrupt: &'static I,
+    tasks: [Option<Task<'a>>; N],
+}
+
+impl<'a, I, const N: usize> ScopedExecutor<'a, I, N> {
+    /// Create a new empty executor.
+    pub const fn new(interrupt: &'static I) -> Self {
+        Self {
+            interrupt,
+            tasks: [const { None }; N],
+        }
+    }
+
+    /// Returns a reference to the interrupt this executor pends.
+    pub fn interrupt(&self) -> &'static I {
+        self.interrupt
+    }
+
+    /// Returns the number of tasks that haven't completed.
+    pub fn len(&self) -> usize {
+        self.tasks.iter().filter(|task| task.is_some()).count()
+    }
+
+    /// Returns `true` if every task has completed.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Spawn a pinned [`Future`] into the first free slot.
+    /// This method returns Ok(()) if a slot was free and Err(future) if the executor was full.
+    pub fn spawn<F>(&mut self, future: Pin<&'a mut F>) -> Result<(), Pin<&'a mut F>>
+    where
+        F: Future<Output = ()> + 'a,
+    {
+        match self.tasks.iter_mut().find(|task| task.is_none()) {
+            Some(task) => {
+                *task = Some(future);
+                Ok(())
+            }
+            None => Err(future),
+        }
+    }
+}
+
+impl<'a, I, const N: usize> ScopedExecutor<'a, I, N>
+where
+    I: Interrupt + Sync,
+{
+    /// Poll every task once, removing the tasks that complete.
+    ///
+    /// This method returns `Poll::Ready(())` once every task has completed.
+    pub fn poll(&mut self) -> Poll<()> {
+        let waker = waker(self.interrupt);
+        self.poll_with(&mut Context::from_waker(&waker))
+    }
+
+    /// Run every task to completion, calling `wait()` whenever they're all pending
+    /// and none were woken since they were last polled.
+    ///
+    /// Tasks are woken through a counter of this call, like [`block_on`](crate::block_on),
+    /// instead of by pending the interrupt.
+    pub fn run(&mut self, wait: impl FnMut()) {
+        crate::block_on::run(|cx| self.poll_with(cx), wait)
+    }
+
+    fn poll_with(&mut self, cx: &mut Context) -> Poll<()> {
+        for slot in &mut self.tasks {
+            if let Some(task) = slot {
+                if crate::task::poll(task.as_mut(), cx).is_ready() {
+                    *slot = None;
+                }
+            }
+        }
+
+        if self.is_empty() {
+            Poll::Ready(())
+        } else {
+            Poll::Pending
+        }
+    }
+}
+
+impl<const N: usize> ScopedExecutor<'_, NonPending, N> {
+    pub const fn non_pending() -> Self {
+        Self::new(&NonPending)
+    }
+}
@@ -0,0 +1,70 @@
+use super::{Arena, Interrupt, StaticExecutor};
+use futures::Future;
+
+/// Handle for spawning tasks onto an [`Arena`] in a [`StaticExecutor`].
+///
+/// Spawners are [`Copy`] and [`Send`], so they can be passed into running tasks,
+/// including tasks on executors bound to other interrupts, to start more tasks later.
+/// Spawning returns the future back if every slot of the arena is in use.
+/// ```
+/// use async_hal::executor::{Arena, NonPending, Spawner, StaticExecutor};
+///
+/// static EXECUTOR: StaticExecutor<Arena<NonPending, 2>> = StaticExecutor::arena(NonPending);
+///
+/// async_hal::static_task! {
+///     async fn child() {}
+/// }
+///
+/// async_hal::static_task! {
+///     async fn parent(spawner: Spawner<NonPending, 2>) {
+///         spawner.spawn(child().unwrap()).ok().unwrap();
+///     }
+/// }
+///
+/// let spawner = EXECUTOR.spawner();
+/// spawner.spawn(parent(spawner).unwrap()).ok().unwrap();
+/// assert!(EXECUTOR.poll().is_ready());
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "executor")))]
+pub struct Spawner<I: 'static, const N: usize> {
+    executor: &'static StaticExecutor<Arena<I, N>>,
+}
+
+impl<I, const N: usize> Spawner<I, N> {
+    pub(super) fn new(executor: &'static StaticExecutor<Arena<I, N>>) -> Self {
+        Self { executor }
+    }
+}
+
+impl<I, const N: usize> Spawner<I, N>
+where
+    I: Interrupt + Sync,
+{
+    /// Spawn a [`Future`] into the first free slot of the arena and pend its interrupt.
+    /// This method returns Ok(()) if a slot was free and Err(future) if the arena was full.
+    pub fn spawn<F>(&self, future: &'static mut F) -> Result<(), &'static mut F>
+    where
+        F: Future<Output = ()> + Send,
+    {
+        self.executor.spawn(future)
+    }
+
+    /// Spawn a boxed [`Future`] into the first free slot of the arena.
+    /// See [`Arena::spawn_boxed`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    pub fn spawn_boxed<F>(&self, future: F) -> Result<(), F>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.executor.spawn_boxed(future)
+    }
+}
+
+impl<I, const N: usize> Clone for Spawner<I, N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I, const N: usize> Copy for Spawner<I, N> {}
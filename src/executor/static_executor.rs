@@ -0,0 +1,309 @@
+use super::{Arena, Executor, Interrupt, Spawner};
+use core::{
+    cell::Cell,
+    future::poll_fn,
+    task::{Context, Poll},
+};
+use critical_section::Mutex;
+use futures::{task::AtomicWaker, Future};
+
+/// Safe `static` storage for an [`Executor`] or [`Arena`].
+///
+/// Executors aren't [`Sync`], so using one directly from `main` and an interrupt handler
+/// requires a `static mut`.
+/// This wrapper owns the executor and serializes access to it instead,
+/// which requires its tasks to be [`Send`]:
+/// short operations like spawning run inside a critical section,
+/// while polling only marks the executor as busy so higher priority interrupts can still run.
+/// Operations attempted while the executor is being polled, such as from the task itself,
+/// fail instead of aliasing it,
+/// except for spawning onto an arena which claims its slots atomically.
+/// ```
+/// use async_hal::executor::{Arena, NonPending, StaticExecutor};
+///
+/// static EXECUTOR: StaticExecutor<Arena<NonPending, 1>> = StaticExecutor::arena(NonPending);
+///
+/// // In `main`
+/// EXECUTOR.spawn(Box::leak(Box::new(async {}))).ok().unwrap();
+///
+/// // In the interrupt handler
+/// assert!(EXECUTOR.poll().is_ready());
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "executor")))]
+pub struct StaticExecutor<E> {
+    executor: E,
+    is_polling: Mutex<Cell<bool>>,
+    repoll: Mutex<Cell<bool>>,
+    contended: AtomicWaker,
+}
+
+// Safety: the executor is only accessed inside a critical section or while it's marked as polling
+unsafe impl<I, F> Sync for StaticExecutor<Executor<I, F>>
+where
+    I: Sync,
+    F: Future + Send,
+    F::Output: Send,
+{
+}
+
+// Safety: as above, except spawning which claims slots atomically,
+// and the arena starts empty so every task was spawned with a `Send` bound
+unsafe impl<I: Sync, const N: usize> Sync for StaticExecutor<Arena<I, N>> {}
+
+impl<E> StaticExecutor<E> {
+    const fn from_executor(executor: E) -> Self {
+        Self {
+            executor,
+            is_polling: Mutex::new(Cell::new(false)),
+            repoll: Mutex::new(Cell::new(false)),
+            contended: AtomicWaker::new(),
+        }
+    }
+
+    /// Run `f` with the executor in a critical section,
+    /// returning `None` if the executor is currently being polled.
+    fn with<R>(&self, f: impl FnOnce(&E) -> R) -> Option<R> {
+        critical_section::with(|cs| {
+            if self.is_polling.borrow(cs).get() {
+                None
+            } else {
+                Some(f(&self.executor))
+            }
+        })
+    }
+
+    /// Mark the executor as polling while running `f`,
+    /// returning `None` if the executor is already being polled.
+    ///
+    /// Polls attempted in the meantime, such as from a nested interrupt, make `f` run again
+    /// before the executor is released, so wakes they were handling aren't lost.
+    fn poll_with<R>(&'static self, f: impl Fn(&'static E) -> R) -> Option<R> {
+        let is_polling = critical_section::with(|cs| {
+            let is_polling = self.is_polling.borrow(cs).replace(true);
+            if is_polling {
+                self.repoll.borrow(cs).set(true);
+            }
+            is_polling
+        });
+        if is_polling {
+            return None;
+        }
+
+        let mut guard = PollGuard {
+            executor: self,
+            is_done: false,
+        };
+
+        let mut output = f(&self.executor);
+        while !guard.finish() {
+            output = f(&self.executor);
+        }
+
+        Some(output)
+    }
+
+    /// Spawn a future with `spawn`, returning the future if the executor is being polled.
+    fn spawn_with<T>(&self, value: T, spawn: impl FnOnce(&E, T) -> Result<(), T>) -> Result<(), T> {
+        let mut value = Some(value);
+        self.with(|executor| spawn(executor, value.take().unwrap()))
+            .unwrap_or_else(|| Err(value.take().unwrap()))
+    }
+}
+
+/// Releases a [`StaticExecutor`] after it's polled, even if a task panics.
+struct PollGuard<'a, E> {
+    executor: &'a StaticExecutor<E>,
+    is_done: bool,
+}
+
+impl<E> PollGuard<'_, E> {
+    /// Release the executor, returning `false` instead if it should be polled again.
+    fn finish(&mut self) -> bool {
+        let executor = self.executor;
+        self.is_done = critical_section::with(|cs| {
+            if executor.repoll.borrow(cs).replace(false) {
+                false
+            } else {
+                executor.is_polling.borrow(cs).set(false);
+                true
+            }
+        });
+        self.is_done
+    }
+}
+
+impl<E> Drop for PollGuard<'_, E> {
+    fn drop(&mut self) {
+        let executor = self.executor;
+        if !self.is_done {
+            critical_section::with(|cs| {
+                executor.repoll.borrow(cs).set(false);
+                executor.is_polling.borrow(cs).set(false);
+            });
+        }
+        executor.contended.wake();
+    }
+}
+
+impl<I, F: Future> StaticExecutor<Executor<I, F>> {
+    /// Create a new empty [`Executor`] that pends `interrupt`.
+    pub const fn executor(interrupt: I) -> Self {
+        Self::from_executor(Executor::new(interrupt))
+    }
+
+    /// Returns a reference to the interrupt this executor pends.
+    pub fn interrupt(&self) -> &I {
+        self.executor.interrupt()
+    }
+}
+
+impl<I, F> StaticExecutor<Executor<I, F>>
+where
+    I: Interrupt + Sync,
+    F: Future,
+{
+    /// Spawn a single [`Future`] on the executor.
+    /// This method returns Ok(()) if the executor was empty and Err(value) if it was full or busy.
+    pub fn spawn(&self, future: F) -> Result<(), F> {
+        self.spawn_with(future, Executor::spawn)
+    }
+
+    /// Replace the current [`Future`] with `future`,
+    /// queueing the restart if the executor is being polled.
+    /// See [`Executor::respawn`].
+    pub fn respawn(&self, future: F) {
+        critical_section::with(|cs| {
+            if self.is_polling.borrow(cs).get() {
+                self.executor.queue_restart(cs, future);
+                self.executor.interrupt().pend();
+            } else {
+                self.executor.respawn(future);
+            }
+        })
+    }
+
+    /// Cancel the current [`Future`].
+    /// See [`Executor::cancel`].
+    pub fn cancel(&self) -> bool {
+        self.with(Executor::cancel).unwrap_or(false)
+    }
+
+    /// Returns `true` if the spawned [`Future`] has completed.
+    pub fn is_finished(&self) -> bool {
+        self.with(Executor::is_finished).unwrap_or(false)
+    }
+
+    /// Take the output of the completed [`Future`], if it hasn't been taken already.
+    pub fn take_output(&self) -> Option<F::Output> {
+        self.with(Executor::take_output).flatten()
+    }
+
+    /// Wait for the spawned [`Future`] to complete and return its output.
+    /// See [`Executor::join`].
+    pub fn join(&'static self) -> impl Future<Output = F::Output> {
+        poll_fn(move |cx| self.poll_join(cx))
+    }
+
+    fn poll_join(&self, cx: &mut Context) -> Poll<F::Output> {
+        self.contended.register(cx.waker());
+        self.with(|executor| executor.join_waker.register(cx.waker()));
+
+        match self.take_output() {
+            Some(output) => Poll::Ready(output),
+            None => Poll::Pending,
+        }
+    }
+
+    /// Poll the current [`Future`] on the executor.
+    /// See [`Executor::poll`].
+    ///
+    /// This method returns `Poll::Pending` if the executor is already being polled.
+    pub fn poll(&'static self) -> Poll<()> {
+        self.poll_with(Executor::poll).unwrap_or(Poll::Pending)
+    }
+
+    /// Returns the stats of the current task.
+    /// See [`Executor::stats`].
+    ///
+    /// Stats can be read even while the executor is being polled.
+    #[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> crate::stats::TaskStats {
+        self.executor.stats()
+    }
+}
+
+impl<I, const N: usize> StaticExecutor<Arena<I, N>> {
+    /// Create a new empty [`Arena`] that pends `interrupt`.
+    pub const fn arena(interrupt: I) -> Self {
+        Self::from_executor(Arena::new(interrupt))
+    }
+
+    /// Returns a reference to the interrupt this arena pends.
+    pub fn interrupt(&self) -> &I {
+        self.executor.interrupt()
+    }
+}
+
+impl<I, const N: usize> StaticExecutor<Arena<I, N>>
+where
+    I: Interrupt + Sync,
+{
+    /// Spawn a [`Future`] into the first free slot of the arena.
+    /// This method returns Ok(()) if a slot was free and Err(future) if the arena was full.
+    ///
+    /// Unlike other operations, this also works while the arena is being polled,
+    /// such as from one of its tasks.
+    pub fn spawn<F>(&self, future: &'static mut F) -> Result<(), &'static mut F>
+    where
+        F: Future<Output = ()> + Send,
+    {
+        self.executor.spawn(future)
+    }
+
+    /// Spawn a boxed [`Future`] into the first free slot of the arena.
+    /// See [`Arena::spawn_boxed`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    pub fn spawn_boxed<F>(&self, future: F) -> Result<(), F>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        self.executor.spawn_boxed(future)
+    }
+
+    /// Create a [`Spawner`] for this arena that can be copied into tasks.
+    pub fn spawner(&'static self) -> Spawner<I, N> {
+        Spawner::new(self)
+    }
+
+    /// Returns the number of tasks currently stored in the arena,
+    /// or `None` if the arena is being polled.
+    pub fn len(&self) -> Option<usize> {
+        self.with(Arena::len)
+    }
+
+    /// Returns `true` if the arena contains no tasks,
+    /// or `None` if the arena is being polled.
+    pub fn is_empty(&self) -> Option<bool> {
+        self.with(Arena::is_empty)
+    }
+
+    /// Poll every task on the arena that has been woken since the last poll.
+    /// See [`Arena::poll`].
+    ///
+    /// This method returns `Poll::Pending` if the arena is already being polled.
+    pub fn poll(&'static self) -> Poll<()> {
+        self.poll_with(Arena::poll).unwrap_or(Poll::Pending)
+    }
+
+    /// Returns the stats of each slot.
+    /// See [`Arena::stats`].
+    ///
+    /// Stats can be read even while the arena is being polled.
+    #[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> [crate::stats::TaskStats; N] {
+        self.executor.stats()
+    }
+}
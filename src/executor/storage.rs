@@ -0,0 +1,225 @@
+//! Static storage for tasks on stable Rust.
+//!
+//! The type of the future returned by an `async fn` can't be named without
+//! `#![feature(type_alias_impl_trait)]`, but its size and alignment can still be computed
+//! in a constant expression from the function itself.
+//! [`static_task!`](crate::static_task) uses this to reserve a [`TaskStorage`]
+//! that fits exactly one task created by the function.
+
+use core::{
+    cell::UnsafeCell,
+    future::Future,
+    mem::{self, MaybeUninit},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Correctly sized and aligned storage for a single `'static` future.
+///
+/// This is usually created by the [`static_task!`](crate::static_task) macro.
+/// ```
+/// use async_hal::executor::TaskStorage;
+///
+/// static STORAGE: TaskStorage<16, 8> = TaskStorage::new();
+///
+/// let task = STORAGE.init(async {}).unwrap();
+/// assert!(STORAGE.init(async {}).is_none());
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "executor")))]
+#[repr(C)]
+pub struct TaskStorage<const SIZE: usize, const ALIGN: usize>
+where
+    Align<ALIGN>: Alignment,
+{
+    _align: [<Align<ALIGN> as Alignment>::Archetype; 0],
+    bytes: UnsafeCell<MaybeUninit<[u8; SIZE]>>,
+    is_taken: AtomicBool,
+}
+
+// Safety: the storage is only ever handed out once by `init`
+unsafe impl<const SIZE: usize, const ALIGN: usize> Sync for TaskStorage<SIZE, ALIGN> where
+    Align<ALIGN>: Alignment
+{
+}
+
+impl<const SIZE: usize, const ALIGN: usize> TaskStorage<SIZE, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    /// Create new empty storage.
+    pub const fn new() -> Self {
+        Self {
+            _align: [],
+            bytes: UnsafeCell::new(MaybeUninit::uninit()),
+            is_taken: AtomicBool::new(false),
+        }
+    }
+
+    /// Move `future` into the storage and return a `'static` reference to it,
+    /// ready to be spawned on an [`Arena`](super::Arena).
+    ///
+    /// Storage can only be initialized once, so this method returns `None` if it was already used.
+    /// Checking that `F` fits the storage happens at compile time.
+    #[allow(clippy::mut_from_ref)]
+    pub fn init<F: Future>(&'static self, future: F) -> Option<&'static mut F> {
+        const {
+            assert!(
+                mem::size_of::<F>() <= SIZE,
+                "future is too large for its storage"
+            );
+            assert!(
+                mem::align_of::<F>() <= ALIGN,
+                "future is overaligned for its storage"
+            );
+        }
+
+        if self.is_taken.swap(true, Ordering::AcqRel) {
+            return None;
+        }
+
+        let ptr = self.bytes.get().cast::<F>();
+
+        // Safety: the storage is large enough and aligned for `F`,
+        // and the flag above ensures this is the only reference to it
+        unsafe {
+            ptr.write(future);
+            Some(&mut *ptr)
+        }
+    }
+}
+
+impl<const SIZE: usize, const ALIGN: usize> Default for TaskStorage<SIZE, ALIGN>
+where
+    Align<ALIGN>: Alignment,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Marker for an alignment of `N` bytes.
+#[doc(hidden)]
+pub struct Align<const N: usize>;
+
+/// Alignments that can be used for [`TaskStorage`].
+#[doc(hidden)]
+pub trait Alignment {
+    type Archetype;
+}
+
+macro_rules! alignments {
+    ($($n:literal => $archetype:ident),*) => {
+        $(
+            #[doc(hidden)]
+            #[repr(align($n))]
+            pub struct $archetype;
+
+            impl Alignment for Align<$n> {
+                type Archetype = $archetype;
+            }
+        )*
+    };
+}
+
+alignments!(
+    1 => Align1,
+    2 => Align2,
+    4 => Align4,
+    8 => Align8,
+    16 => Align16,
+    32 => Align32,
+    64 => Align64,
+    128 => Align128,
+    256 => Align256
+);
+
+/// Function that creates a task from `Args`.
+#[doc(hidden)]
+pub trait TaskFn<Args> {
+    type Future: Future;
+}
+
+macro_rules! task_fns {
+    ($($arg:ident),*) => {
+        impl<T, Fut, $($arg),*> TaskFn<($($arg,)*)> for T
+        where
+            T: FnOnce($($arg),*) -> Fut,
+            Fut: Future,
+        {
+            type Future = Fut;
+        }
+    };
+}
+
+task_fns!();
+task_fns!(A);
+task_fns!(A, B);
+task_fns!(A, B, C);
+task_fns!(A, B, C, D);
+task_fns!(A, B, C, D, E);
+task_fns!(A, B, C, D, E, G);
+task_fns!(A, B, C, D, E, G, H);
+task_fns!(A, B, C, D, E, G, H, J);
+
+/// Returns the size of the future created by `task`.
+#[doc(hidden)]
+pub const fn size_of_task<T: TaskFn<Args>, Args>(_task: &T) -> usize {
+    mem::size_of::<T::Future>()
+}
+
+/// Returns the alignment of the future created by `task`.
+#[doc(hidden)]
+pub const fn align_of_task<T: TaskFn<Args>, Args>(_task: &T) -> usize {
+    mem::align_of::<T::Future>()
+}
+
+/// Declare an `async fn` whose task is stored in static memory, on stable Rust.
+///
+/// The macro replaces the function with one that creates the task
+/// in its own correctly sized and aligned [`TaskStorage`].
+/// It returns a `&'static mut` reference to the task that can be spawned on an
+/// [`Arena`](crate::executor::Arena), or `None` if the task was already created.
+///
+/// All arguments are bound mutably inside the task.
+/// ```
+/// use async_hal::executor::{Arena, NonPending};
+///
+/// async_hal::static_task! {
+///     async fn count(to: u32) {
+///         for _ in 0..to {
+///             core::future::ready(()).await;
+///         }
+///     }
+/// }
+///
+/// let arena: &'static Arena<NonPending, 1> = Box::leak(Box::new(Arena::non_pending()));
+/// arena.spawn(count(3).unwrap()).ok().unwrap();
+/// assert!(arena.poll().is_ready());
+///
+/// // Each task has storage for a single future
+/// assert!(count(1).is_none());
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "executor")))]
+#[macro_export]
+macro_rules! static_task {
+    (
+        $(#[$attr:meta])*
+        $vis:vis async fn $name:ident($($arg:ident: $ty:ty),* $(,)?) $(-> $output:ty)? $body:block
+    ) => {
+        $(#[$attr])*
+        $vis fn $name(
+            $($arg: $ty),*
+        ) -> ::core::option::Option<
+            &'static mut impl ::core::future::Future<Output = ($($output)?)>,
+        > {
+            #[allow(unused_mut)]
+            async fn task($(mut $arg: $ty),*) $(-> $output)? $body
+
+            static STORAGE: $crate::executor::TaskStorage<
+                { $crate::executor::storage::size_of_task(&task) },
+                { $crate::executor::storage::align_of_task(&task) },
+            > = $crate::executor::TaskStorage::new();
+
+            STORAGE.init(task($($arg),*))
+        }
+    };
+}
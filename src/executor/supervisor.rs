@@ -0,0 +1,96 @@
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures::{ready, Future};
+use pin_project_lite::pin_project;
+
+pin_project! {
+    /// Future that restarts a task in place whenever it completes with an error.
+    ///
+    /// Each restart drops the failed task and creates a new one by calling `spawn`,
+    /// then yields back to the executor by waking itself.
+    /// The supervisor completes with the output of the first task that succeeds,
+    /// or with the last error once the restart limit set by [`Supervisor::max_restarts`] is reached.
+    /// ```
+    /// use async_hal::executor::Supervisor;
+    ///
+    /// let mut attempts = 0;
+    /// let supervisor = Supervisor::new(|| {
+    ///     attempts += 1;
+    ///     let attempt = attempts;
+    ///     async move {
+    ///         if attempt < 3 {
+    ///             Err("Crashed!")
+    ///         } else {
+    ///             Ok(attempt)
+    ///         }
+    ///     }
+    /// });
+    /// futures::pin_mut!(supervisor);
+    ///
+    /// assert_eq!(async_hal::block_on(supervisor, || {}), Ok(3));
+    /// ```
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct Supervisor<S, F> {
+        spawn: S,
+        #[pin]
+        task: F,
+        restarts: usize,
+        max_restarts: Option<usize>,
+    }
+}
+
+impl<S, F> Supervisor<S, F>
+where
+    S: FnMut() -> F,
+{
+    /// Create a new supervisor and spawn its first task.
+    pub fn new(mut spawn: S) -> Self {
+        Self {
+            task: spawn(),
+            spawn,
+            restarts: 0,
+            max_restarts: None,
+        }
+    }
+
+    /// Limit the number of times the task can be restarted.
+    pub fn max_restarts(mut self, max_restarts: usize) -> Self {
+        self.max_restarts = Some(max_restarts);
+        self
+    }
+
+    /// Returns the number of times the task has been restarted.
+    pub fn restarts(&self) -> usize {
+        self.restarts
+    }
+}
+
+impl<S, F, T, E> Future for Supervisor<S, F>
+where
+    S: FnMut() -> F,
+    F: Future<Output = Result<T, E>>,
+{
+    type Output = Result<T, E>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let mut me = self.project();
+
+        let error = match ready!(me.task.as_mut().poll(cx)) {
+            Ok(output) => return Poll::Ready(Ok(output)),
+            Err(error) => error,
+        };
+
+        if me.max_restarts.is_some_and(|max| *me.restarts >= max) {
+            return Poll::Ready(Err(error));
+        }
+
+        // Replace the failed task in place, dropping it
+        me.task.set((me.spawn)());
+        *me.restarts += 1;
+
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
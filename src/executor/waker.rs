@@ -0,0 +1,54 @@
+use super::Interrupt;
+use core::{
+    marker::PhantomData,
+    task::{RawWaker, RawWakerVTable, Waker},
+};
+
+/// Create a [`Waker`] that pends `interrupt` when woken.
+///
+/// The waker only stores a thin pointer to `interrupt` and uses a vtable specific to `I`,
+/// so cloning and dropping it are free.
+/// Wakers can be sent to and woken from any context, which is why `I` must be [`Sync`].
+/// ```
+/// use async_hal::executor::{self, NonPending};
+///
+/// let waker = executor::waker(&NonPending);
+/// waker.wake_by_ref();
+/// ```
+pub fn waker<I>(interrupt: &'static I) -> Waker
+where
+    I: Interrupt + Sync,
+{
+    let raw_waker = RawWaker::new(interrupt as *const I as *const (), &Pend::<I>::VTABLE);
+
+    // Safety: `interrupt` is valid for `'static` and `Pend` upholds the `RawWaker` contract
+    unsafe { Waker::from_raw(raw_waker) }
+}
+
+struct Pend<I> {
+    _marker: PhantomData<I>,
+}
+
+impl<I> Pend<I>
+where
+    I: Interrupt + Sync,
+{
+    const VTABLE: RawWakerVTable =
+        RawWakerVTable::new(Self::clone, Self::wake, Self::wake, Self::drop);
+
+    /// # Safety
+    /// `ptr` must have been created from a `&'static I` in [`waker`].
+    unsafe fn clone(ptr: *const ()) -> RawWaker {
+        RawWaker::new(ptr, &Self::VTABLE)
+    }
+
+    /// # Safety
+    /// `ptr` must have been created from a `&'static I` in [`waker`].
+    unsafe fn wake(ptr: *const ()) {
+        let interrupt = unsafe { &*ptr.cast::<I>() };
+        interrupt.pend();
+    }
+
+    /// Nothing to release, the interrupt is borrowed for `'static`.
+    unsafe fn drop(_ptr: *const ()) {}
+}
@@ -22,7 +22,7 @@ pub trait Interrupt {
 
     /// Enable the interrupt and return a [`Stream`] of events.
     /// This will disable the interrupt on drop.
-    fn interrupts(&mut self) -> Interrupts<Self>
+    fn interrupts(&mut self) -> Interrupts<'_, Self>
     where
         Self: Unpin,
     {
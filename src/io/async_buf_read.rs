@@ -1,4 +1,6 @@
 use super::AsyncRead;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
 use core::{
     pin::Pin,
     task::{Context, Poll},
@@ -49,6 +51,20 @@ pub trait AsyncBufRead: AsyncRead {
     fn consume(self: Pin<&mut Self>, amt: usize);
 }
 
+#[cfg(feature = "alloc")]
+impl<T: ?Sized + AsyncBufRead + Unpin> AsyncBufRead for Box<T> {
+    fn poll_fill_buf(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<&[u8], Self::Error>> {
+        Pin::new(&mut **self.get_mut()).poll_fill_buf(cx)
+    }
+
+    fn consume(mut self: Pin<&mut Self>, amt: usize) {
+        Pin::new(&mut **self).consume(amt)
+    }
+}
+
 impl AsyncBufRead for &[u8] {
     fn poll_fill_buf(
         self: Pin<&mut Self>,
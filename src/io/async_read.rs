@@ -1,4 +1,10 @@
 use super::Read;
+#[cfg(feature = "alloc")]
+use super::ReadToEnd;
+#[cfg(feature = "delay")]
+use crate::delay::{self, DelayMs, Timeout};
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec::Vec};
 use core::{
     pin::Pin,
     task::{Context, Poll},
@@ -45,6 +51,55 @@ pub trait AsyncRead {
     {
         Read::new(self, buf)
     }
+
+    /// Reads all bytes until EOF, appending them to `buf` and growing it as needed.
+    ///
+    /// The returned future resolves to the number of bytes read.
+    /// ```
+    /// use async_hal::io::AsyncRead;
+    ///
+    /// let mut bytes = [1, 2, 3].as_ref();
+    /// let mut buf = vec![0];
+    ///
+    /// let amt = async_hal::block_on(bytes.read_to_end(&mut buf), || {}).unwrap();
+    /// assert_eq!(amt, 3);
+    /// assert_eq!(buf, [0, 1, 2, 3]);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    fn read_to_end<'a>(&'a mut self, buf: &'a mut Vec<u8>) -> ReadToEnd<'a, Self>
+    where
+        Self: Unpin,
+    {
+        ReadToEnd::new(self, buf)
+    }
+
+    /// Read some bytes into `buf`, giving up if none arrive before a delay of `ms` milliseconds expires.
+    /// See [`delay::timeout`].
+    /// ```
+    /// use async_hal::{delay, io::AsyncRead};
+    ///
+    /// let mut bytes = [1, 2, 3].as_ref();
+    /// let mut buf = [0; 3];
+    /// let mut delay = delay::ready::<u32>();
+    ///
+    /// let amt = async_hal::block_on(bytes.read_timeout(&mut buf, &mut delay, 50), || {});
+    /// assert!(matches!(amt, Ok(Ok(3))));
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "delay")))]
+    #[cfg(feature = "delay")]
+    fn read_timeout<'a, D>(
+        &'a mut self,
+        buf: &'a mut [u8],
+        delay: &'a mut D,
+        ms: D::Delay,
+    ) -> Timeout<'a, D, Read<'a, Self>>
+    where
+        Self: Unpin,
+        D: DelayMs + Unpin,
+    {
+        delay::timeout(delay, ms, self.read(buf))
+    }
 }
 
 macro_rules! deref_async_read {
@@ -61,6 +116,8 @@ macro_rules! deref_async_read {
 
 #[cfg(feature = "alloc")]
 impl<T: ?Sized + AsyncRead + Unpin> AsyncRead for Box<T> {
+    type Error = T::Error;
+
     deref_async_read!();
 }
 
@@ -1,3 +1,5 @@
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, vec::Vec};
 use core::{
     ops::DerefMut,
     pin::Pin,
@@ -67,6 +69,23 @@ impl<T: ?Sized + AsyncWrite + Unpin> AsyncWrite for &mut T {
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<T: ?Sized + AsyncWrite + Unpin> AsyncWrite for Box<T> {
+    type Error = T::Error;
+
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context,
+        buf: &[u8],
+    ) -> Poll<Result<usize, Self::Error>> {
+        Pin::new(&mut **self).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Pin::new(&mut **self).poll_flush(cx)
+    }
+}
+
 impl<P> AsyncWrite for Pin<P>
 where
     P: DerefMut + Unpin,
@@ -96,7 +115,7 @@ impl AsyncWrite for &'_ mut [u8] {
         buf: &[u8],
     ) -> Poll<Result<usize, Self::Error>> {
         let amt = core::cmp::min(buf.len(), self.len());
-        let (a, b) = core::mem::replace(&mut *self, &mut []).split_at_mut(amt);
+        let (a, b) = core::mem::take(&mut *self).split_at_mut(amt);
         a.copy_from_slice(&buf[..amt]);
         *self = b;
         Poll::Ready(Ok(amt))
@@ -106,3 +125,22 @@ impl AsyncWrite for &'_ mut [u8] {
         Poll::Ready(Ok(()))
     }
 }
+
+/// Appends written bytes to the vector, growing it as needed.
+#[cfg(feature = "alloc")]
+impl AsyncWrite for Vec<u8> {
+    type Error = Void;
+
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context,
+        buf: &[u8],
+    ) -> Poll<Result<usize, Self::Error>> {
+        self.get_mut().extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
@@ -1,5 +1,8 @@
 use super::{AsyncBufRead, AsyncRead};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
 use core::{
+    ops::{Deref, DerefMut},
     pin::Pin,
     task::{Context, Poll},
 };
@@ -22,26 +25,63 @@ pin_project! {
     /// When the `BufReader` is dropped, the contents of its buffer will be
     /// discarded. Creating multiple instances of a `BufReader` on the same
     /// stream can cause data loss.
+    ///
+    /// The buffer is either borrowed, such as from a `static` array,
+    /// or allocated on the heap with [`BufReader::with_capacity`].
     pub struct BufReader<'buf, R> {
         #[pin]
         inner: R,
-        buf: &'buf mut [u8],
+        buf: Buf<'buf>,
         pos: usize,
         cap: usize,
     }
 }
 
+/// Storage for the buffer of a [`BufReader`].
+enum Buf<'buf> {
+    Borrowed(&'buf mut [u8]),
+    #[cfg(feature = "alloc")]
+    Owned(Vec<u8>),
+}
+
+impl Deref for Buf<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Borrowed(buf) => buf,
+            #[cfg(feature = "alloc")]
+            Self::Owned(buf) => buf,
+        }
+    }
+}
+
+impl DerefMut for Buf<'_> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        match self {
+            Self::Borrowed(buf) => buf,
+            #[cfg(feature = "alloc")]
+            Self::Owned(buf) => buf,
+        }
+    }
+}
+
 impl<'buf, R: AsyncRead> BufReader<'buf, R> {
     /// Creates a new `BufReader` with the specified buffer capacity.
     pub fn new(buf: &'buf mut [u8], inner: R) -> Self {
         Self {
             inner,
-            buf,
+            buf: Buf::Borrowed(buf),
             pos: 0,
             cap: 0,
         }
     }
 
+    /// Returns the capacity of the internal buffer.
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
     /// Gets a reference to the underlying reader.
     ///
     /// It is inadvisable to directly read from the underlying reader.
@@ -86,6 +126,40 @@ impl<'buf, R: AsyncRead> BufReader<'buf, R> {
     }
 }
 
+#[cfg(feature = "alloc")]
+impl<R: AsyncRead> BufReader<'static, R> {
+    /// Creates a new `BufReader` with a heap-allocated buffer of `capacity` bytes.
+    /// ```
+    /// use async_hal::io::{AsyncRead, BufReader};
+    ///
+    /// let mut reader = BufReader::with_capacity(4, [1, 2, 3].as_ref());
+    /// let mut buf = [0; 2];
+    ///
+    /// let amt = async_hal::block_on(reader.read(&mut buf), || {}).unwrap();
+    /// assert_eq!(&buf[..amt], [1, 2]);
+    /// assert_eq!(reader.buffer(), [3]);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn with_capacity(capacity: usize, inner: R) -> Self {
+        Self {
+            inner,
+            buf: Buf::Owned(vec![0; capacity]),
+            pos: 0,
+            cap: 0,
+        }
+    }
+
+    /// Grow the heap-allocated buffer by `additional` bytes, keeping any buffered data.
+    ///
+    /// This does nothing if the buffer is borrowed.
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    pub fn reserve(&mut self, additional: usize) {
+        if let Buf::Owned(buf) = &mut self.buf {
+            buf.resize(buf.len() + additional, 0);
+        }
+    }
+}
+
 impl<R: AsyncRead> AsyncRead for BufReader<'_, R> {
     type Error = R::Error;
 
@@ -125,8 +199,7 @@ impl<R: AsyncRead> AsyncBufRead for BufReader<'_, R> {
         if *me.pos >= *me.cap {
             debug_assert!(*me.pos == *me.cap);
 
-            ready!(me.inner.poll_read(cx, me.buf))?;
-            *me.cap = me.buf.len();
+            *me.cap = ready!(me.inner.poll_read(cx, me.buf))?;
             *me.pos = 0;
         }
         Poll::Ready(Ok(&me.buf[*me.pos..*me.cap]))
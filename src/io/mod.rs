@@ -24,6 +24,11 @@ pub mod queue;
 mod read;
 pub use read::Read;
 
+#[cfg(feature = "alloc")]
+mod read_to_end;
+#[cfg(feature = "alloc")]
+pub use read_to_end::ReadToEnd;
+
 mod write_all;
 pub use write_all::WriteAll;
 
@@ -19,13 +19,19 @@ impl<const N: usize> Queue<N> {
         }
     }
 
-    pub fn try_split(&self) -> bbqueue::Result<(Reader<N>, Writer<N>)> {
+    pub fn try_split(&self) -> bbqueue::Result<(Reader<'_, N>, Writer<'_, N>)> {
         self.queue
             .try_split()
             .map(|(tx, rx)| (Reader { queue: self, rx }, Writer { queue: self, tx }))
     }
 }
 
+impl<const N: usize> Default for Queue<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct Reader<'a, const N: usize> {
     queue: &'a Queue<N>,
     rx: Consumer<'a, N>,
@@ -40,12 +46,12 @@ impl<const N: usize> AsyncRead for Reader<'_, N> {
         buf: &mut [u8],
     ) -> Poll<Result<usize, Self::Error>> {
         let bytes = self.rx.read().unwrap();
-        if bytes.len() == 0 {
+        if bytes.is_empty() {
             self.queue.waker.register(cx.waker());
             return Poll::Pending;
         }
 
-        buf[..bytes.len()].copy_from_slice(&*bytes);
+        buf[..bytes.len()].copy_from_slice(&bytes);
         Poll::Ready(Ok(bytes.len()))
     }
 }
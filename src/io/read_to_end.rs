@@ -0,0 +1,82 @@
+use super::AsyncRead;
+use alloc::vec::Vec;
+use core::{
+    marker::PhantomPinned,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures::{ready, Future};
+use pin_project_lite::pin_project;
+
+/// Number of bytes the buffer grows by when it's full.
+const GROW_BY: usize = 32;
+
+pin_project! {
+    /// Future for the [`read_to_end`](AsyncRead::read_to_end) method.
+    #[derive(Debug)]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct ReadToEnd<'a, R: ?Sized> {
+        reader: &'a mut R,
+        buf: &'a mut Vec<u8>,
+        // Length of the buffer that's been read into, the rest is zeroed spare capacity
+        len: usize,
+        amt: usize,
+        // Make this future `!Unpin` for compatibility with async trait methods.
+        #[pin]
+        _pin: PhantomPinned,
+    }
+
+    impl<R: ?Sized> PinnedDrop for ReadToEnd<'_, R> {
+        fn drop(this: Pin<&mut Self>) {
+            let me = this.project();
+            me.buf.truncate(*me.len);
+        }
+    }
+}
+
+impl<'a, R: ?Sized> ReadToEnd<'a, R> {
+    pub(super) fn new(reader: &'a mut R, buf: &'a mut Vec<u8>) -> Self {
+        Self {
+            len: buf.len(),
+            reader,
+            buf,
+            amt: 0,
+            _pin: PhantomPinned,
+        }
+    }
+}
+
+impl<R> Future for ReadToEnd<'_, R>
+where
+    R: AsyncRead + Unpin + ?Sized,
+{
+    type Output = Result<usize, R::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<usize, R::Error>> {
+        let me = self.project();
+
+        loop {
+            // Zeroed bytes are kept across polls, so the buffer only grows once they're all filled
+            if *me.len == me.buf.len() {
+                me.buf.reserve(GROW_BY);
+                me.buf.resize(me.buf.capacity(), 0);
+            }
+
+            let result = ready!(Pin::new(&mut **me.reader).poll_read(cx, &mut me.buf[*me.len..]));
+            match result {
+                Ok(0) => {
+                    me.buf.truncate(*me.len);
+                    return Poll::Ready(Ok(*me.amt));
+                }
+                Ok(used) => {
+                    *me.len += used;
+                    *me.amt += used;
+                }
+                Err(error) => {
+                    me.buf.truncate(*me.len);
+                    return Poll::Ready(Err(error));
+                }
+            }
+        }
+    }
+}
@@ -5,7 +5,8 @@
 //! This crate provides zero-cost utilities for async IO with `#![no-std]`.
 //!
 //! Two execution models are provided:
-//! - Interrupt mode: Multiple interrupts can each run a future using an [`Executor`].
+//! - Interrupt mode: Multiple interrupts can each run a future using an [`Executor`],
+//!   or a fixed number of futures using an [`executor::Arena`].
 //!   Each future is polled on every interrupt and channels can be used to communicate between them.
 //!
 //! - Thread mode: Interrupts wake a main function where a future is being polled with [`block_on`].
@@ -38,17 +39,40 @@
 //!
 //! [feature flags]: https://doc.rust-lang.org/cargo/reference/manifest.html#the-features-section
 //!
-//! - `full`: Enables all features listed below except `mock` and `bxcan`.
+//! - `full`: Enables all features listed below except `alloc`, `macros`, `rt`, `stats`, `mock` and `bxcan`.
+//! - `alloc`: Enables heap-allocated tasks and buffers, and IO trait implementations for `Box` and `Vec`.
 //! - `can`: Enables the `async_hal::can` module.
 //! - `delay`: Enables the `async_hal::delay` module.
 //! - `executor`: Enables the `async_hal::executor` module.
 //! - `io`: Enables the `async_hal::io` module.
+//! - `macros`: Enables the `#[async_hal::task]` and `#[async_hal::main]` attribute macros.
+//! - `rt`: Enables [`bind_interrupts!`] for declaring `cortex-m-rt` interrupt handlers.
 //! - `serial`: Enables the `async_hal::serial` module.
+//! - `stats`: Enables the `async_hal::stats` module and task instrumentation in executors and [`block_on`].
 //! - `nb`: Enables async wrappers for non-blocking interfaces (such as from `embedded_hal`).
+//! - `mock`: Enables host-side mocks for testing with `std`, such as a simulated interrupt controller,
+//!   and the `std` implementation of `critical-section`.
+//! - `cortex-m`: Enables pending Cortex-M NVIC lines from executor wakers, and `sev` for [`executor::EventFlag`].
 //! - `bxcan`: Enables CAN support for stm32 devices with [`bxcan`](https://docs.rs/bxcan/).
+//!
+//! The `delay`, `executor` and `stats` features use [`critical-section`](https://docs.rs/critical-section/),
+//! so the application must provide an implementation, such as the `critical-section-single-core` feature of `cortex-m`.
+//! The `mock` feature provides one for hosts.
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod block_on;
+pub use block_on::block_on;
+#[cfg(feature = "delay")]
+pub use block_on::block_on_idle;
+
+pub mod coop;
+pub use coop::yield_now;
+
+mod task;
 
-use core::task::{Context, Poll};
-use futures::{task::noop_waker, Future, FutureExt};
+pub mod task_local;
 
 #[cfg_attr(docsrs, doc(cfg(feature = "can")))]
 #[cfg(feature = "can")]
@@ -62,6 +86,20 @@ pub mod executor;
 #[cfg(feature = "executor")]
 pub use executor::Executor;
 
+#[cfg_attr(docsrs, doc(cfg(feature = "macros")))]
+#[cfg(feature = "macros")]
+pub use async_hal_macros::{main, task};
+
+/// Dependencies of the code generated by the macros, which can't assume they're in scope.
+#[doc(hidden)]
+#[cfg(feature = "rt")]
+pub mod __private {
+    pub use cortex_m;
+    pub use cortex_m_rt;
+}
+
+pub mod binding;
+
 /// Interrupt stream
 mod interrupt;
 pub use interrupt::Interrupt;
@@ -81,35 +119,11 @@ pub mod serial;
 /// Delay timers
 pub mod delay;
 
-/// Run `future` to completion and return its output.
-/// This will repeatedly poll the future and call `wait()`.
-///
-/// This is useful for microcontrollers that can be set into a low-power mode while waiting,
-/// such as using Cortex-M's `wfi` instruction.
-/// ```
-/// use futures::pin_mut;
-///
-/// let task = async { true };
-/// pin_mut!(task);
-///
-/// let output = async_hal::block_on(task, || {
-///     dbg!("Waiting!");
-/// });
-/// assert!(output);
-/// ```
-pub fn block_on<F, W>(mut future: F, mut wait: W) -> F::Output
-where
-    F: Future + Unpin,
-    W: FnMut(),
-{
-    let waker = noop_waker();
-
-    loop {
-        let mut cx = Context::from_waker(&waker);
-        if let Poll::Ready(output) = future.poll_unpin(&mut cx) {
-            return output;
-        }
-
-        wait()
-    }
-}
+#[cfg_attr(docsrs, doc(cfg(feature = "stats")))]
+#[cfg(feature = "stats")]
+pub mod stats;
+
+#[cfg_attr(docsrs, doc(cfg(all(feature = "delay", feature = "mock"))))]
+#[cfg(all(feature = "delay", feature = "mock"))]
+/// Deterministic simulation
+pub mod sim;
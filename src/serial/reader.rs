@@ -1,3 +1,4 @@
+use crate::coop;
 /// Read half of a UART serial port.
 use core::{
     marker::PhantomData,
@@ -5,7 +6,7 @@ use core::{
     task::{Context, Poll},
 };
 use embedded_hal::serial::Read;
-use futures::Stream;
+use futures::{ready, Stream};
 
 pub struct Reader<R, W> {
     read: R,
@@ -30,7 +31,9 @@ where
 {
     type Item = Result<W, R::Error>;
 
-    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Option<Self::Item>> {
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        ready!(coop::poll_proceed(cx));
+
         match self.read.read() {
             Ok(word) => Poll::Ready(Some(Ok(word))),
             Err(nb::Error::WouldBlock) => Poll::Pending,
@@ -1,9 +1,10 @@
+use crate::coop;
 use core::{
     pin::Pin,
     task::{Context, Poll},
 };
 use embedded_hal::serial::Write;
-use futures::Sink;
+use futures::{ready, Sink};
 
 /// Write half of a UART serial port.
 pub struct Writer<T, W> {
@@ -31,7 +32,9 @@ where
 {
     type Error = T::Error;
 
-    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        ready!(coop::poll_proceed(cx));
+
         if self.word.is_none() {
             Poll::Ready(Ok(()))
         } else {
@@ -0,0 +1,105 @@
+use crate::coop;
+use core::{
+    cell::RefCell,
+    convert::Infallible,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+use futures::{ready, Sink, Stream};
+use std::{collections::VecDeque, rc::Rc};
+
+struct Shared<T> {
+    queue: VecDeque<T>,
+    waker: Option<Waker>,
+}
+
+/// Create an unbounded channel that stands in for a peripheral,
+/// such as a serial port with `u8` items or a CAN bus with frames.
+///
+/// The [`Receiver`] is a stream of `Result<T, Infallible>`,
+/// so it can be used wherever a [`SerialRead`](crate::serial::SerialRead)
+/// or [`CanReceive`](crate::can::CanReceive) is expected.
+/// The [`Sender`] can be used as a sink from tasks or to inject items with [`Simulation::at`](super::Simulation::at).
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let shared = Rc::new(RefCell::new(Shared {
+        queue: VecDeque::new(),
+        waker: None,
+    }));
+
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
+
+/// Sending half of a [`channel`].
+pub struct Sender<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Sender<T> {
+    /// Send `item` to the receiver, waking it.
+    pub fn send(&self, item: T) {
+        let mut shared = self.shared.borrow_mut();
+        shared.queue.push_back(item);
+        let waker = shared.waker.take();
+        drop(shared);
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Sink<T> for Sender<T> {
+    type Error = Infallible;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Self::Error> {
+        self.send(item);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Receiving half of a [`channel`].
+pub struct Receiver<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = Result<T, Infallible>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        ready!(coop::poll_proceed(cx));
+
+        let mut shared = self.shared.borrow_mut();
+        match shared.queue.pop_front() {
+            Some(item) => Poll::Ready(Some(Ok(item))),
+            None => {
+                shared.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use super::Simulation;
+use crate::delay::DelayMs;
+use core::{
+    convert::Infallible,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Timer on the virtual clock of a [`Simulation`].
+pub struct SimDelay {
+    sim: Simulation,
+    key: usize,
+    deadline: Option<u64>,
+}
+
+impl SimDelay {
+    pub(super) fn new(sim: Simulation) -> Self {
+        Self {
+            key: sim.timer_key(),
+            sim,
+            deadline: None,
+        }
+    }
+}
+
+impl DelayMs for SimDelay {
+    type Delay = u32;
+    type Error = Infallible;
+
+    fn start(&mut self, ms: Self::Delay) -> Result<(), Self::Error> {
+        self.deadline = Some(self.sim.now() + u64::from(ms));
+        Ok(())
+    }
+
+    fn poll_delay_ms(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        let Some(deadline) = self.deadline else {
+            return Poll::Ready(Ok(()));
+        };
+
+        if self.sim.now() >= deadline {
+            self.deadline = None;
+            Poll::Ready(Ok(()))
+        } else {
+            self.sim.set_timer(self.key, deadline, cx.waker());
+            Poll::Pending
+        }
+    }
+
+    fn cancel(&mut self) -> Result<(), Self::Error> {
+        self.deadline = None;
+        self.sim.cancel_timer(self.key);
+        Ok(())
+    }
+}
+
+impl Drop for SimDelay {
+    fn drop(&mut self) {
+        self.sim.cancel_timer(self.key);
+    }
+}
@@ -0,0 +1,301 @@
+//! Deterministic simulation of async-hal applications on the host.
+//!
+//! A [`Simulation`] runs a whole task graph with [`block_on`](crate::block_on),
+//! using [`SimDelay`] timers on a virtual clock and [`channel`]s in place of serial ports or CAN buses.
+//! Virtual time only advances when every task is idle, jumping straight to the next deadline,
+//! so simulations run as fast as the host allows regardless of the delays involved.
+//!
+//! Tasks woken at the same time are polled in an order chosen by the seed of the simulation,
+//! and [`Simulation::random`] can be used to vary inputs such as the timing of injected events.
+//! Running with the same seed always produces the same schedule,
+//! so a timing-dependent bug found with one seed can be reproduced exactly.
+//! Interrupt executors can take part by binding them to the lines of a `MockNvic`,
+//! whose handlers run as soon as the tasks on them are woken.
+//! ```
+//! use async_hal::{delay::DelayMs, sim::Simulation};
+//! use std::{cell::RefCell, rc::Rc};
+//!
+//! let sim = Simulation::new(42);
+//! let log = Rc::new(RefCell::new(Vec::new()));
+//!
+//! let mut delay = sim.delay();
+//! let task_log = log.clone();
+//! sim.spawn(async move {
+//!     delay.delay_ms(500).await.unwrap();
+//!     task_log.borrow_mut().push("blink");
+//! });
+//!
+//! let mut delay = sim.delay();
+//! sim.run(async move {
+//!     delay.delay_ms(1_000).await.unwrap();
+//! });
+//!
+//! assert_eq!(sim.now(), 1_000);
+//! assert_eq!(*log.borrow(), ["blink"]);
+//! ```
+
+use core::{
+    cell::{Cell, RefCell},
+    future::poll_fn,
+    pin::{pin, Pin},
+    task::{Context, Poll, Waker},
+};
+use futures::{task::AtomicWaker, Future};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    rc::Rc,
+    sync::{Arc, Mutex},
+    task::Wake,
+};
+
+mod channel;
+pub use channel::{channel, Receiver, Sender};
+
+mod delay;
+pub use delay::SimDelay;
+
+type Task = Pin<Box<dyn Future<Output = ()>>>;
+
+type Event = Box<dyn FnOnce()>;
+
+/// Key of the future passed to [`Simulation::run`].
+const MAIN: usize = usize::MAX;
+
+/// Seeded, deterministic executor with a virtual clock.
+///
+/// Simulations are cheap to clone and every clone refers to the same simulation,
+/// so they can be moved into tasks to spawn more tasks or read the time.
+#[derive(Clone)]
+pub struct Simulation {
+    inner: Rc<Inner>,
+}
+
+struct Inner {
+    seed: u64,
+    rng: Cell<u64>,
+    now: Cell<u64>,
+    next_task: Cell<usize>,
+    tasks: RefCell<BTreeMap<usize, Task>>,
+    woken: Arc<Woken>,
+    timers: RefCell<BTreeMap<usize, (u64, Waker)>>,
+    next_timer: Cell<usize>,
+    events: RefCell<BTreeMap<(u64, usize), Event>>,
+    next_event: Cell<usize>,
+}
+
+/// Tasks woken since they were last polled.
+#[derive(Default)]
+struct Woken {
+    tasks: Mutex<BTreeSet<usize>>,
+    waker: AtomicWaker,
+}
+
+struct TaskWaker {
+    task: usize,
+    woken: Arc<Woken>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref()
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.tasks.lock().unwrap().insert(self.task);
+        self.woken.waker.wake();
+    }
+}
+
+impl Simulation {
+    /// Create a new simulation at time zero that schedules tasks based on `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self {
+            inner: Rc::new(Inner {
+                seed,
+                rng: Cell::new(seed),
+                now: Cell::new(0),
+                next_task: Cell::new(0),
+                tasks: RefCell::default(),
+                woken: Arc::default(),
+                timers: RefCell::default(),
+                next_timer: Cell::new(0),
+                events: RefCell::default(),
+                next_event: Cell::new(0),
+            }),
+        }
+    }
+
+    /// Returns the seed of this simulation.
+    pub fn seed(&self) -> u64 {
+        self.inner.seed
+    }
+
+    /// Returns the current virtual time in milliseconds.
+    pub fn now(&self) -> u64 {
+        self.inner.now.get()
+    }
+
+    /// Returns a pseudo-random number below `bound` derived from the seed.
+    pub fn random(&self, bound: u64) -> u64 {
+        // SplitMix64
+        let state = self.inner.rng.get().wrapping_add(0x9E37_79B9_7F4A_7C15);
+        self.inner.rng.set(state);
+
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        (z ^ (z >> 31)) % bound
+    }
+
+    /// Create a new timer on the virtual clock.
+    pub fn delay(&self) -> SimDelay {
+        SimDelay::new(self.clone())
+    }
+
+    /// Spawn a task that runs alongside the main future of [`Simulation::run`].
+    pub fn spawn(&self, task: impl Future<Output = ()> + 'static) {
+        let key = self.inner.next_task.get();
+        self.inner.next_task.set(key + 1);
+
+        self.inner.tasks.borrow_mut().insert(key, Box::pin(task));
+        self.inner.woken.tasks.lock().unwrap().insert(key);
+        self.inner.woken.waker.wake();
+    }
+
+    /// Run `event` once the virtual clock reaches `ms`,
+    /// such as to inject bytes into a [`channel`] like a peripheral would.
+    ///
+    /// Events run in the order they were scheduled, before any task is polled at that time.
+    pub fn at(&self, ms: u64, event: impl FnOnce() + 'static) {
+        let key = self.inner.next_event.get();
+        self.inner.next_event.set(key + 1);
+
+        self.inner
+            .events
+            .borrow_mut()
+            .insert((ms.max(self.now()), key), Box::new(event));
+    }
+
+    /// Run `main` and the spawned tasks until `main` completes, returning its output.
+    ///
+    /// # Panics
+    /// Panics if the simulation stalls, with every task waiting but no timers or events pending.
+    pub fn run<F: Future>(&self, main: F) -> F::Output {
+        let mut main = pin!(main);
+        self.inner.woken.tasks.lock().unwrap().insert(MAIN);
+
+        let driver = poll_fn(|cx| {
+            self.inner.woken.waker.register(cx.waker());
+            self.poll_woken(main.as_mut())
+        });
+
+        crate::block_on(driver, || {
+            if !self.advance() {
+                panic!(
+                    "simulation with seed {} stalled at {} ms: every task is waiting without a timer or event pending",
+                    self.seed(),
+                    self.now()
+                )
+            }
+        })
+    }
+
+    /// Poll every woken task in a seeded order until they're all idle.
+    fn poll_woken<F: Future>(&self, mut main: Pin<&mut F>) -> Poll<F::Output> {
+        loop {
+            let mut woken: Vec<_> = core::mem::take(&mut *self.inner.woken.tasks.lock().unwrap())
+                .into_iter()
+                .collect();
+            if woken.is_empty() {
+                return Poll::Pending;
+            }
+
+            // Fisher-Yates shuffle
+            for i in (1..woken.len()).rev() {
+                let j = self.random(i as u64 + 1) as usize;
+                woken.swap(i, j);
+            }
+
+            for key in woken {
+                let waker = Waker::from(Arc::new(TaskWaker {
+                    task: key,
+                    woken: self.inner.woken.clone(),
+                }));
+                let mut cx = Context::from_waker(&waker);
+
+                // Each task is polled like on its own executor, despite sharing a poll of `block_on`
+                if key == MAIN {
+                    if let Poll::Ready(output) = crate::task::poll(main.as_mut(), &mut cx) {
+                        return Poll::Ready(output);
+                    }
+                    continue;
+                }
+
+                // Remove the task while it's polled so it can spawn others
+                let Some(mut task) = self.inner.tasks.borrow_mut().remove(&key) else {
+                    continue;
+                };
+                if crate::task::poll(task.as_mut(), &mut cx).is_pending() {
+                    self.inner.tasks.borrow_mut().insert(key, task);
+                }
+            }
+        }
+    }
+
+    /// Advance the virtual clock to the next deadline, running due events and waking due timers.
+    /// Returns `false` if nothing is scheduled.
+    fn advance(&self) -> bool {
+        let next_timer = self.inner.timers.borrow().values().map(|(at, _)| *at).min();
+        let next_event = self.inner.events.borrow().keys().next().map(|(at, _)| *at);
+        let Some(now) = next_timer.into_iter().chain(next_event).min() else {
+            return false;
+        };
+        self.inner.now.set(self.now().max(now));
+
+        loop {
+            let mut events = self.inner.events.borrow_mut();
+            let Some(entry) = events.first_entry() else {
+                break;
+            };
+            if entry.key().0 > now {
+                break;
+            }
+            let event = entry.remove();
+            drop(events);
+
+            event();
+        }
+
+        let mut due = Vec::new();
+        self.inner.timers.borrow_mut().retain(|_, (at, waker)| {
+            if *at <= now {
+                due.push(waker.clone());
+            }
+            *at > now
+        });
+        for waker in due {
+            waker.wake();
+        }
+
+        true
+    }
+
+    fn timer_key(&self) -> usize {
+        let key = self.inner.next_timer.get();
+        self.inner.next_timer.set(key + 1);
+        key
+    }
+
+    /// Wake `waker` once the virtual clock reaches `deadline`,
+    /// replacing any deadline previously set for the same timer.
+    fn set_timer(&self, key: usize, deadline: u64, waker: &Waker) {
+        self.inner
+            .timers
+            .borrow_mut()
+            .insert(key, (deadline, waker.clone()));
+    }
+
+    fn cancel_timer(&self, key: usize) {
+        self.inner.timers.borrow_mut().remove(&key);
+    }
+}
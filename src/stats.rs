@@ -0,0 +1,143 @@
+//! Task instrumentation for starvation debugging.
+//!
+//! With the `stats` feature enabled, every [`Executor`](crate::Executor) task, [`Arena`](crate::executor::Arena) slot
+//! and [`block_on`](crate::block_on) call records how often it was polled and woken,
+//! how long it spent in `poll` and its longest single poll.
+//! Times are measured in ticks of the [`Monotonic`] clock set with [`set_clock`],
+//! such as a [`FreeRunning`](crate::delay::FreeRunning) clock over a cycle counter, and are zero until a clock is set.
+//!
+//! [`TaskStats`] implements [`Display`](fmt::Display), so it can be written to any [`core::fmt::Write`],
+//! or logged with `defmt::Display2Format`.
+//! ```
+//! use async_hal::{delay::FreeRunning, stats};
+//!
+//! static CLOCK: FreeRunning<fn() -> u32, 1_000_000> = FreeRunning::new(|| 0);
+//!
+//! stats::set_clock(&CLOCK);
+//! async_hal::block_on(async {}, || {});
+//!
+//! let stats = stats::block_on();
+//! assert_eq!(stats.polls, 1);
+//! assert_eq!(stats.to_string(), "polls: 1, wakes: 0, busy: 0, longest: 0");
+//! ```
+
+use crate::delay::{Instant, Monotonic};
+use core::{cell::Cell, fmt};
+use critical_section::Mutex;
+
+/// [`Monotonic`] clock of any tick rate.
+trait Ticks: Sync {
+    fn ticks(&self) -> u64;
+}
+
+impl<M, const HZ: u32> Ticks for M
+where
+    M: Monotonic<Instant = Instant<HZ>> + Sync,
+{
+    fn ticks(&self) -> u64 {
+        self.now().ticks()
+    }
+}
+
+static CLOCK: Mutex<Cell<Option<&'static dyn Ticks>>> = Mutex::new(Cell::new(None));
+
+pub(crate) static BLOCK_ON: Stats = Stats::new();
+
+/// Set the clock used to measure time spent in `poll`.
+///
+/// On Cortex-M a [`FreeRunning`](crate::delay::FreeRunning) clock over the DWT cycle counter is a good choice.
+pub fn set_clock<M, const HZ: u32>(clock: &'static M)
+where
+    M: Monotonic<Instant = Instant<HZ>> + Sync,
+{
+    critical_section::with(|cs| CLOCK.borrow(cs).set(Some(clock)))
+}
+
+fn now() -> u64 {
+    critical_section::with(|cs| CLOCK.borrow(cs).get()).map_or(0, |clock| clock.ticks())
+}
+
+/// Returns the stats of the current (or last) call to [`block_on`](crate::block_on).
+pub fn block_on() -> TaskStats {
+    BLOCK_ON.get()
+}
+
+/// Snapshot of the stats of a task.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TaskStats {
+    /// Number of times the task was polled.
+    pub polls: u32,
+    /// Number of times the task's waker was woken.
+    pub wakes: u32,
+    /// Total clock ticks spent polling the task.
+    pub busy: u64,
+    /// Clock ticks spent in the longest single poll.
+    pub longest: u64,
+}
+
+impl TaskStats {
+    const EMPTY: Self = Self {
+        polls: 0,
+        wakes: 0,
+        busy: 0,
+        longest: 0,
+    };
+}
+
+impl fmt::Display for TaskStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "polls: {}, wakes: {}, busy: {}, longest: {}",
+            self.polls, self.wakes, self.busy, self.longest
+        )
+    }
+}
+
+/// Stats of a single task, shared between its executor and wakers.
+pub(crate) struct Stats {
+    stats: Mutex<Cell<TaskStats>>,
+}
+
+impl Stats {
+    pub(crate) const fn new() -> Self {
+        Self {
+            stats: Mutex::new(Cell::new(TaskStats::EMPTY)),
+        }
+    }
+
+    pub(crate) fn get(&self) -> TaskStats {
+        critical_section::with(|cs| self.stats.borrow(cs).get())
+    }
+
+    pub(crate) fn reset(&self) {
+        critical_section::with(|cs| self.stats.borrow(cs).set(TaskStats::EMPTY))
+    }
+
+    pub(crate) fn record_wake(&self) {
+        self.update(|stats| stats.wakes = stats.wakes.saturating_add(1))
+    }
+
+    /// Run `poll`, recording it as a poll of this task.
+    pub(crate) fn measure<R>(&self, poll: impl FnOnce() -> R) -> R {
+        let start = now();
+        let output = poll();
+        let elapsed = now().saturating_sub(start);
+
+        self.update(|stats| {
+            stats.polls = stats.polls.saturating_add(1);
+            stats.busy = stats.busy.saturating_add(elapsed);
+            stats.longest = stats.longest.max(elapsed);
+        });
+        output
+    }
+
+    fn update(&self, f: impl FnOnce(&mut TaskStats)) {
+        critical_section::with(|cs| {
+            let cell = self.stats.borrow(cs);
+            let mut stats = cell.get();
+            f(&mut stats);
+            cell.set(stats);
+        })
+    }
+}
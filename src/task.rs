@@ -0,0 +1,15 @@
+use crate::{coop, task_local};
+use core::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use futures::Future;
+
+/// Poll a task the way every executor does:
+/// with a fresh [`coop`] budget and no [`task_local`] values in scope from the code that polls it.
+pub(crate) fn poll<F>(future: Pin<&mut F>, cx: &mut Context) -> Poll<F::Output>
+where
+    F: Future + ?Sized,
+{
+    coop::with_budget(cx, |cx| task_local::isolate(|| future.poll(cx)))
+}
@@ -0,0 +1,238 @@
+//! Task-local storage.
+//!
+//! A [`LocalKey`] gives futures access to a value provided by an enclosing [`LocalKey::scope`],
+//! however deeply they're nested, without passing it through every function.
+//! Values are stored inline in the [`TaskLocalFuture`] returned by `scope`, so their size is known at compile time.
+//!
+//! Executors and [`block_on`](crate::block_on) poll each task with no values in scope,
+//! so a task only sees the values of scopes it's running in,
+//! even when it preempts another task that has values in scope.
+//! The innermost scope is shared by the whole program, like the stack of a single core,
+//! so scoped futures must not be polled by several threads or cores at once.
+//! ```
+//! use core::sync::atomic::{AtomicU32, Ordering};
+//!
+//! async_hal::task_local! {
+//!     static NODE_ID: u8;
+//!     static ERRORS: AtomicU32;
+//! }
+//!
+//! async fn handle_frame() {
+//!     let id = NODE_ID.get();
+//!     if id != 7 {
+//!         ERRORS.with(|errors| errors.fetch_add(1, Ordering::Relaxed));
+//!     }
+//! }
+//!
+//! let task = NODE_ID.scope(7, ERRORS.scope(AtomicU32::new(0), async {
+//!     handle_frame().await;
+//!     ERRORS.with(|errors| errors.load(Ordering::Relaxed))
+//! }));
+//!
+//! assert_eq!(async_hal::block_on(task, || {}), 0);
+//! assert!(NODE_ID.try_with(|_| ()).is_err());
+//! ```
+
+use core::{
+    fmt,
+    marker::PhantomData,
+    pin::Pin,
+    ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+    task::{Context, Poll},
+};
+use futures::Future;
+use pin_project_lite::pin_project;
+
+/// Value provided by an active scope, linked to the scope it's nested in.
+struct Scope {
+    key: *const (),
+    value: *const (),
+    parent: *const Scope,
+}
+
+/// Innermost scope being polled, or null.
+///
+/// Scopes are swapped in for the duration of their poll and swapped back out afterwards,
+/// so they're pushed and popped in LIFO order by nested polls and preempting interrupts.
+static CURRENT: AtomicPtr<Scope> = AtomicPtr::new(ptr::null_mut());
+
+fn current() -> *const Scope {
+    CURRENT.load(Ordering::Acquire)
+}
+
+fn replace(scope: *const Scope) -> *const Scope {
+    CURRENT.swap(scope.cast_mut(), Ordering::AcqRel)
+}
+
+/// Restores the previous scope when dropped, even if a poll panics.
+struct Restore {
+    scope: *const Scope,
+    previous: *const Scope,
+}
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let scope = replace(self.previous);
+        debug_assert!(
+            ptr::eq(scope, self.scope),
+            "task-local scopes must be polled by one thread or core at a time"
+        );
+    }
+}
+
+/// Run `f` with no task-local values in scope.
+pub(crate) fn isolate<R>(f: impl FnOnce() -> R) -> R {
+    let _restore = Restore {
+        scope: ptr::null(),
+        previous: replace(ptr::null()),
+    };
+    f()
+}
+
+/// Declare [`LocalKey`]s for task-local values.
+/// ```
+/// async_hal::task_local! {
+///     pub static TAG: &'static str;
+/// }
+///
+/// let tag = async_hal::block_on(TAG.scope("can", async { TAG.get() }), || {});
+/// assert_eq!(tag, "can");
+/// ```
+#[macro_export]
+macro_rules! task_local {
+    ($($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty;)*) => {
+        $(
+            $(#[$attr])*
+            $vis static $name: $crate::task_local::LocalKey<$ty> =
+                $crate::task_local::LocalKey::new();
+        )*
+    };
+}
+
+/// Key for a task-local value, usually declared with [`task_local!`](crate::task_local).
+///
+/// Values are shared with any code that runs while their scope is being polled,
+/// including interrupt handlers that preempt it, so they must be [`Sync`] to declare a `static` key.
+pub struct LocalKey<T: 'static> {
+    // Keys are identified by their address, which must be unique
+    _unique: u8,
+    _marker: PhantomData<T>,
+}
+
+impl<T: 'static> LocalKey<T> {
+    /// Create a new key.
+    pub const fn new() -> Self {
+        Self {
+            _unique: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Run `future` with `value` in scope for this key.
+    pub fn scope<F: Future>(&'static self, value: T, future: F) -> TaskLocalFuture<T, F> {
+        TaskLocalFuture {
+            key: self,
+            value,
+            future,
+        }
+    }
+
+    /// Call `f` with a reference to the value in scope for this key.
+    ///
+    /// # Panics
+    /// Panics if this key has no value in scope.
+    /// See [`LocalKey::try_with`] for a non-panicking version.
+    pub fn with<R>(&'static self, f: impl FnOnce(&T) -> R) -> R {
+        self.try_with(f)
+            .expect("task-local value accessed outside of its scope")
+    }
+
+    /// Call `f` with a reference to the value in scope for this key,
+    /// returning an [`AccessError`] if no value is in scope.
+    pub fn try_with<R>(&'static self, f: impl FnOnce(&T) -> R) -> Result<R, AccessError> {
+        let mut scope = current();
+
+        // Safety: scopes are only linked while the `TaskLocalFuture` that owns them is being polled,
+        // which encloses this call
+        while let Some(current) = unsafe { scope.as_ref() } {
+            if current.key == self as *const Self as *const () {
+                // Safety: values are only stored under the key of their own type
+                let value = unsafe { &*current.value.cast::<T>() };
+                return Ok(f(value));
+            }
+            scope = current.parent;
+        }
+
+        Err(AccessError { _priv: () })
+    }
+
+    /// Returns a copy of the value in scope for this key.
+    ///
+    /// # Panics
+    /// Panics if this key has no value in scope.
+    pub fn get(&'static self) -> T
+    where
+        T: Copy,
+    {
+        self.with(|value| *value)
+    }
+}
+
+impl<T: 'static> Default for LocalKey<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Error returned by [`LocalKey::try_with`] when the key has no value in scope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AccessError {
+    _priv: (),
+}
+
+impl fmt::Display for AccessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("task-local value accessed outside of its scope")
+    }
+}
+
+pin_project! {
+    /// Future that runs a future with a task-local value in scope.
+    ///
+    /// Created by the [`LocalKey::scope`] method.
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct TaskLocalFuture<T: 'static, F> {
+        key: &'static LocalKey<T>,
+        value: T,
+        #[pin]
+        future: F,
+    }
+}
+
+impl<T, F> TaskLocalFuture<T, F> {
+    /// Returns a reference to the value this future provides.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T, F: Future> Future for TaskLocalFuture<T, F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let me = self.project();
+
+        let scope = Scope {
+            key: *me.key as *const LocalKey<T> as *const (),
+            value: me.value as *const T as *const (),
+            parent: current(),
+        };
+        let _restore = Restore {
+            scope: &scope,
+            previous: replace(&scope),
+        };
+
+        me.future.poll(cx)
+    }
+}
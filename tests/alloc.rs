@@ -0,0 +1,116 @@
+#[cfg(all(feature = "alloc", feature = "executor", feature = "io"))]
+mod tests {
+    use async_hal::{
+        executor::{Arena, NonPending},
+        io::{self, AsyncRead, BufReader},
+    };
+    use core::{
+        convert::Infallible,
+        future::Future,
+        pin::{pin, Pin},
+        task::{Context, Poll},
+    };
+    use futures::task::noop_waker_ref;
+    use std::{collections::VecDeque, sync::Arc};
+
+    /// Reader that's pending before each chunk of bytes.
+    struct Chunks {
+        chunks: VecDeque<&'static [u8]>,
+        is_pending: bool,
+    }
+
+    impl Chunks {
+        fn new(chunks: impl IntoIterator<Item = &'static [u8]>) -> Self {
+            Self {
+                chunks: chunks.into_iter().collect(),
+                is_pending: true,
+            }
+        }
+    }
+
+    impl AsyncRead for Chunks {
+        type Error = Infallible;
+
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context,
+            buf: &mut [u8],
+        ) -> Poll<Result<usize, Self::Error>> {
+            self.is_pending = !self.is_pending;
+            if !self.is_pending {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+
+            let chunk = self.chunks.pop_front().unwrap_or_default();
+            buf[..chunk.len()].copy_from_slice(chunk);
+            Poll::Ready(Ok(chunk.len()))
+        }
+    }
+
+    #[test]
+    fn it_spawns_boxed_tasks() {
+        let arena: &'static Arena<NonPending, 1> = Box::leak(Box::new(Arena::non_pending()));
+        let state = Arc::new(());
+
+        let task = {
+            let state = state.clone();
+            async move { drop(state) }
+        };
+        arena.spawn_boxed(task).ok().unwrap();
+        assert!(arena.spawn_boxed(async {}).is_err());
+        assert_eq!(Arc::strong_count(&state), 2);
+
+        // Completed tasks are freed with their slot
+        assert!(arena.poll().is_ready());
+        assert_eq!(Arc::strong_count(&state), 1);
+        arena.spawn_boxed(async {}).ok().unwrap();
+    }
+
+    #[test]
+    fn it_grows_io_buffers() {
+        let bytes: Vec<u8> = (0..100).collect();
+        let mut reader = Box::new(bytes.as_slice());
+
+        let mut buf = Vec::new();
+        let amt = async_hal::block_on(reader.read_to_end(&mut buf), || {}).unwrap();
+        assert_eq!(amt, 100);
+        assert_eq!(buf, bytes);
+
+        let mut reader = BufReader::with_capacity(8, bytes.as_slice());
+        assert_eq!(reader.capacity(), 8);
+        reader.reserve(8);
+        assert_eq!(reader.capacity(), 16);
+
+        let mut writer = Box::new(Vec::new());
+        let amt = async_hal::block_on(io::copy_buf(&mut reader, &mut writer), || {}).unwrap();
+        assert_eq!(amt, 100);
+        assert_eq!(*writer, bytes);
+    }
+
+    #[test]
+    fn it_reads_to_end_across_pending_polls() {
+        let mut reader = Chunks::new([[1, 2].as_ref(), &[3]]);
+        let mut buf = vec![0];
+
+        let amt = async_hal::block_on(reader.read_to_end(&mut buf), || {}).unwrap();
+        assert_eq!(amt, 3);
+        assert_eq!(buf, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn it_keeps_bytes_read_by_dropped_futures() {
+        let mut reader = Chunks::new([[1, 2].as_ref(), &[3]]);
+        let mut buf = Vec::new();
+
+        {
+            let mut read = pin!(reader.read_to_end(&mut buf));
+            let mut cx = Context::from_waker(noop_waker_ref());
+            assert!(read.as_mut().poll(&mut cx).is_pending());
+            assert!(read.as_mut().poll(&mut cx).is_pending());
+        }
+
+        // The zeroed spare capacity is removed when the future is dropped
+        assert_eq!(buf, [1, 2]);
+    }
+}
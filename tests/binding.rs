@@ -0,0 +1,112 @@
+#[cfg(all(feature = "can", feature = "mock", feature = "nb", feature = "rt"))]
+mod tests {
+    use async_hal::{
+        binding::Driver,
+        can::{receive::Receive, DualReceiver, MockFrame, Receiver},
+    };
+    use core::convert::Infallible;
+    use embedded_hal::can::{Frame, StandardId};
+    use futures::StreamExt;
+    use std::{collections::VecDeque, sync::Mutex};
+
+    static FIFOS: Mutex<[VecDeque<MockFrame>; 3]> =
+        Mutex::new([VecDeque::new(), VecDeque::new(), VecDeque::new()]);
+
+    /// Receive FIFO backed by [`FIFOS`].
+    struct MockFifo<const N: usize>;
+
+    impl<const N: usize> MockFifo<N> {
+        /// Receive `data` into this FIFO and run its interrupt handler.
+        fn push(data: &[u8], handler: unsafe extern "C" fn()) {
+            let frame = MockFrame::new(StandardId::ZERO, data).unwrap();
+            FIFOS.lock().unwrap()[N].push_back(frame);
+            unsafe { handler() };
+        }
+    }
+
+    impl<const N: usize> Receive for MockFifo<N> {
+        type Frame = MockFrame;
+        type Error = Infallible;
+
+        fn receive(&mut self) -> nb::Result<Self::Frame, Self::Error> {
+            FIFOS.lock().unwrap()[N]
+                .pop_front()
+                .ok_or(nb::Error::WouldBlock)
+        }
+    }
+
+    #[allow(non_camel_case_types)]
+    enum interrupt {
+        TEST_CAN_RX0,
+        TEST_CAN_RX1,
+        TEST_CAN_RX2,
+    }
+
+    #[allow(non_camel_case_types)]
+    enum TEST_CAN_RX0 {}
+    #[allow(non_camel_case_types)]
+    enum TEST_CAN_RX1 {}
+    #[allow(non_camel_case_types)]
+    enum TEST_CAN_RX2 {}
+
+    impl Driver for MockFifo<0> {
+        type Interrupt = TEST_CAN_RX0;
+    }
+
+    impl Driver for MockFifo<1> {
+        type Interrupt = TEST_CAN_RX1;
+    }
+
+    impl Driver for MockFifo<2> {
+        type Interrupt = TEST_CAN_RX2;
+    }
+
+    // The handlers are exported under their vector names, like the vector table would see them
+    extern "C" {
+        fn TEST_CAN_RX0();
+        fn TEST_CAN_RX1();
+        fn TEST_CAN_RX2();
+    }
+
+    async_hal::bind_interrupts! {
+        struct Irqs {
+            TEST_CAN_RX0 => MockFifo<0>;
+            TEST_CAN_RX1 => MockFifo<1>;
+            TEST_CAN_RX2 => MockFifo<2>;
+        }
+    }
+
+    #[test]
+    fn it_wakes_bound_drivers_from_handlers() {
+        // The receiver is only polled again once its handler runs
+        let mut rx = Receiver::bind(MockFifo::<0>, Irqs);
+        let mut waits = 0;
+        let frame = async_hal::block_on(rx.next(), || {
+            waits += 1;
+            MockFifo::<0>::push(&[1], TEST_CAN_RX0);
+        });
+        assert_eq!(frame.unwrap().unwrap().data, [1]);
+        assert_eq!(waits, 1);
+    }
+
+    #[test]
+    fn it_wakes_dual_receivers_from_either_handler() {
+        let mut rx = DualReceiver::bind(MockFifo::<1>, MockFifo::<2>, Irqs);
+
+        let mut waits = 0;
+        let frame = async_hal::block_on(rx.next(), || {
+            waits += 1;
+            MockFifo::<1>::push(&[1], TEST_CAN_RX1);
+        });
+        assert_eq!(frame.unwrap().unwrap().data, [1]);
+        assert_eq!(waits, 1);
+
+        let mut waits = 0;
+        let frame = async_hal::block_on(rx.next(), || {
+            waits += 1;
+            MockFifo::<2>::push(&[2], TEST_CAN_RX2);
+        });
+        assert_eq!(frame.unwrap().unwrap().data, [2]);
+        assert_eq!(waits, 1);
+    }
+}
@@ -0,0 +1,75 @@
+use async_hal::block_on;
+use core::{future::poll_fn, task::Poll};
+use std::{cell::Cell, sync::Mutex, task::Waker};
+
+#[test]
+fn it_skips_waiting_when_woken_during_the_poll() {
+    let waits = Cell::new(0);
+    let mut polls = 0;
+    let task = poll_fn(|cx| {
+        polls += 1;
+        if polls == 3 {
+            Poll::Ready(polls)
+        } else {
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    });
+    assert_eq!(block_on(task, || waits.set(waits.get() + 1)), 3);
+    assert_eq!(waits.get(), 0);
+}
+
+#[test]
+fn it_waits_until_woken() {
+    // Woken from `wait`, like an interrupt handler would
+    let waits = Cell::new(0);
+    let waker: Mutex<Option<Waker>> = Mutex::new(None);
+    let mut is_ready = false;
+    let task = poll_fn(|cx| {
+        if is_ready {
+            Poll::Ready(())
+        } else {
+            is_ready = true;
+            *waker.lock().unwrap() = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    });
+    block_on(task, || {
+        waits.set(waits.get() + 1);
+        waker.lock().unwrap().take().unwrap().wake();
+    });
+    assert_eq!(waits.get(), 1);
+}
+
+#[test]
+fn it_ignores_wakes_for_other_calls() {
+    let outer = poll_fn(|cx| {
+        let outer_waker = cx.waker().clone();
+        let mut polls = 0;
+        let mut waits = 0;
+        let inner = poll_fn(|_| {
+            polls += 1;
+            outer_waker.wake_by_ref();
+            if polls == 2 {
+                Poll::Ready(())
+            } else {
+                Poll::Pending
+            }
+        });
+        block_on(inner, || waits += 1);
+        assert_eq!(waits, 1);
+        Poll::Ready(())
+    });
+    block_on(outer, || {});
+}
+
+#[test]
+fn it_pins_futures() {
+    let task = async {
+        let value = 1;
+        let value_ref = &value;
+        core::future::ready(()).await;
+        *value_ref + 1
+    };
+    assert_eq!(block_on(task, || {}), 2);
+}
@@ -0,0 +1,22 @@
+#[cfg(feature = "io")]
+mod tests {
+    use async_hal::io::{AsyncRead, BufReader};
+
+    #[test]
+    fn it_only_buffers_bytes_read() {
+        let mut storage = [0; 8];
+        let mut reader = BufReader::new(&mut storage, [1, 2, 3].as_ref());
+        let mut buf = [0; 2];
+
+        let amt = async_hal::block_on(reader.read(&mut buf), || {}).unwrap();
+        assert_eq!(&buf[..amt], [1, 2]);
+        assert_eq!(reader.buffer(), [3]);
+
+        // The rest of the buffer was never filled by the inner reader
+        let amt = async_hal::block_on(reader.read(&mut buf), || {}).unwrap();
+        assert_eq!(&buf[..amt], [3]);
+
+        let amt = async_hal::block_on(reader.read(&mut buf), || {}).unwrap();
+        assert_eq!(amt, 0);
+    }
+}
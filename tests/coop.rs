@@ -0,0 +1,113 @@
+#[cfg(all(feature = "executor", feature = "nb", feature = "serial"))]
+mod tests {
+    use async_hal::{
+        coop::{self, BUDGET},
+        executor::{Arena, NonPending},
+        serial::Reader,
+    };
+    use core::{
+        cell::Cell,
+        pin::pin,
+        sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+        task::Context,
+    };
+    use embedded_hal::serial::Read;
+    use futures::{future, task::noop_waker_ref, StreamExt};
+
+    /// Serial port that always has another byte available.
+    struct Flood<'a> {
+        reads: &'a Cell<usize>,
+    }
+
+    impl Read<u8> for Flood<'_> {
+        type Error = ();
+
+        fn read(&mut self) -> nb::Result<u8, Self::Error> {
+            self.reads.set(self.reads.get() + 1);
+            Ok(0)
+        }
+    }
+
+    async fn drain(reads: &Cell<usize>) {
+        let mut reader = Reader::new(Flood { reads });
+        while reader.next().await.is_some() {}
+    }
+
+    fn noop_cx() -> Context<'static> {
+        Context::from_waker(noop_waker_ref())
+    }
+
+    #[test]
+    fn it_limits_work_in_a_select() {
+        // A flood of bytes can't starve another future in the same `select`
+        let reads = Cell::new(0);
+        let other = async {
+            coop::yield_now().await;
+            "done"
+        };
+        let (drain, other) = (pin!(drain(&reads)), pin!(other));
+        let output = async_hal::block_on(future::select(drain, other), || {});
+        assert!(matches!(output, future::Either::Right(("done", _))));
+        assert_eq!(reads.get(), 2 * usize::from(BUDGET));
+    }
+
+    #[test]
+    fn it_limits_work_per_task() {
+        // A flood of bytes can't starve another task on the same executor
+        static FLOOD_READS: AtomicUsize = AtomicUsize::new(0);
+        static IS_DONE: AtomicBool = AtomicBool::new(false);
+
+        async fn flood() {
+            let reads = Cell::new(0);
+            let mut reader = Reader::new(Flood { reads: &reads });
+            while !IS_DONE.load(Ordering::SeqCst) {
+                reader.next().await;
+                FLOOD_READS.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let arena: &'static Arena<NonPending, 2> = Box::leak(Box::new(Arena::non_pending()));
+        arena.spawn(Box::leak(Box::new(flood()))).ok().unwrap();
+        arena
+            .spawn(Box::leak(Box::new(async {
+                IS_DONE.store(true, Ordering::SeqCst)
+            })))
+            .ok()
+            .unwrap();
+
+        assert!(arena.poll().is_pending());
+        assert_eq!(FLOOD_READS.load(Ordering::SeqCst), usize::from(BUDGET));
+        assert!(arena.poll().is_ready());
+    }
+
+    #[test]
+    fn it_is_unconstrained_outside_of_executors() {
+        let mut cx = noop_cx();
+        for _ in 0..usize::from(BUDGET) * 2 {
+            assert!(coop::poll_proceed(&mut cx).is_ready());
+        }
+        assert!(coop::has_budget_remaining(&cx));
+    }
+
+    #[test]
+    fn it_gives_each_poll_its_own_budget() {
+        coop::with_budget(&mut noop_cx(), |outer| {
+            while coop::poll_proceed(outer).is_ready() {}
+            assert!(!coop::has_budget_remaining(outer));
+
+            // A nested poll, like an executor polled from a task, starts with a fresh budget
+            coop::with_budget(outer, |inner| {
+                assert!(coop::has_budget_remaining(inner));
+                for _ in 0..BUDGET {
+                    assert!(coop::poll_proceed(inner).is_ready());
+                }
+                assert!(coop::poll_proceed(inner).is_pending());
+            });
+            assert!(!coop::has_budget_remaining(outer));
+        });
+
+        // Wakers cloned during a poll outlive its budget
+        let waker = coop::with_budget(&mut noop_cx(), |cx| cx.waker().clone());
+        waker.wake();
+    }
+}
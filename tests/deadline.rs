@@ -0,0 +1,122 @@
+#[cfg(all(feature = "delay", feature = "executor", feature = "mock"))]
+mod tests {
+    use async_hal::{
+        block_on_idle,
+        delay::{Deadlines, DelayMs, Duration, Instant, MockClock, Monotonic, Tracked},
+        executor::{Executor, NonPending},
+    };
+    use core::{
+        pin::Pin,
+        task::{Context, Poll},
+    };
+    use futures::future;
+
+    /// Delay that expires once the mock clock reaches its deadline.
+    struct SimDelay {
+        clock: MockClock<1_000>,
+        deadline: Option<Instant<1_000>>,
+    }
+
+    impl DelayMs for SimDelay {
+        type Delay = u32;
+        type Error = ();
+
+        fn start(&mut self, ms: u32) -> Result<(), ()> {
+            self.deadline = Some(self.clock.now() + Duration::millis(ms.into()));
+            Ok(())
+        }
+
+        fn poll_delay_ms(mut self: Pin<&mut Self>, _cx: &mut Context) -> Poll<Result<(), ()>> {
+            match self.deadline {
+                Some(deadline) if self.clock.now() >= deadline => {
+                    self.deadline = None;
+                    Poll::Ready(Ok(()))
+                }
+                _ => Poll::Pending,
+            }
+        }
+
+        fn cancel(&mut self) -> Result<(), ()> {
+            self.deadline = None;
+            Ok(())
+        }
+    }
+
+    type MockDeadlines = Deadlines<MockClock<1_000>, 1_000>;
+
+    fn deadlines() -> MockDeadlines {
+        Deadlines::new(MockClock::new())
+    }
+
+    fn tracked(deadlines: &MockDeadlines) -> Tracked<'_, SimDelay, MockClock<1_000>, 1_000> {
+        let delay = SimDelay {
+            clock: deadlines.clock().clone(),
+            deadline: None,
+        };
+        Tracked::new(delay, deadlines).unwrap()
+    }
+
+    #[test]
+    fn it_reports_next_deadline_to_block_on_idle() {
+        let deadlines = deadlines();
+        let mut short = tracked(&deadlines);
+        let mut long = tracked(&deadlines);
+        let mut reported = Vec::new();
+
+        // Block on two concurrent delays, sleeping until each deadline
+        block_on_idle(
+            async {
+                let (a, b) = future::join(long.delay_ms(30), short.delay_ms(10)).await;
+                a.and(b)
+            },
+            &deadlines,
+            |deadline| {
+                reported.push(deadline.unwrap().ticks());
+                deadlines.clock().set(deadline.unwrap());
+            },
+        )
+        .unwrap();
+
+        assert_eq!(reported, [10, 30]);
+        assert_eq!(deadlines.next_deadline(), None);
+    }
+
+    #[test]
+    fn it_keeps_deadlines_of_other_clocks_apart() {
+        let deadlines = deadlines();
+        let other = self::deadlines();
+        other.clock().advance(Duration::millis(1_000));
+
+        let mut delay = tracked(&other);
+        delay.start(5).unwrap();
+        assert_eq!(other.next_deadline(), Some(Instant::from_ticks(1_005)));
+        assert_eq!(deadlines.next_deadline(), None);
+    }
+
+    #[test]
+    fn it_reports_next_deadline_to_poll_idle() {
+        let deadlines: &'static MockDeadlines = Box::leak(Box::new(deadlines()));
+        let executor: &'static Executor<NonPending, _> =
+            Box::leak(Box::new(Executor::non_pending()));
+        executor
+            .spawn(async {
+                let mut delay = tracked(deadlines);
+                delay.delay_ms(5).await.unwrap();
+            })
+            .ok()
+            .unwrap();
+
+        // Poll an interrupt executor, reprogramming the wakeup after each poll
+        let mut wakeup = None;
+        assert!(executor
+            .poll_idle(deadlines, |deadline| wakeup = deadline)
+            .is_pending());
+        assert_eq!(wakeup, Some(Instant::from_ticks(5)));
+
+        deadlines.clock().advance(Duration::millis(5));
+        assert!(executor
+            .poll_idle(deadlines, |_| panic!("Finished executors aren't idle"))
+            .is_ready());
+        assert_eq!(deadlines.next_deadline(), None);
+    }
+}
@@ -0,0 +1,358 @@
+#[cfg(feature = "executor")]
+mod tests {
+    use async_hal::executor::{
+        self, Arena, EventFlag, Executor, Interrupt, NonPending, PendCounter, ScopedExecutor,
+        Spawner, StaticExecutor,
+    };
+    use core::{
+        cell::{Cell, RefCell},
+        future::poll_fn,
+        pin::{pin, Pin},
+        sync::atomic::{AtomicUsize, Ordering},
+        task::{Context, Poll, Waker},
+    };
+    use futures::Future;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct CountPends {
+        count: AtomicUsize,
+    }
+
+    impl Interrupt for CountPends {
+        fn pend(&self) {
+            self.count.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[derive(Default)]
+    struct Pending {
+        polls: usize,
+        waker: Option<Waker>,
+        is_done: bool,
+    }
+
+    /// Future that stays pending until `is_done` is set, counting each poll.
+    struct Task(&'static Mutex<Pending>);
+
+    impl Future for Task {
+        type Output = ();
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+            let mut state = self.0.lock().unwrap();
+            state.polls += 1;
+
+            if state.is_done {
+                Poll::Ready(())
+            } else {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    fn task() -> (&'static Mutex<Pending>, &'static mut Task) {
+        let state = Box::leak(Box::default());
+        (state, Box::leak(Box::new(Task(state))))
+    }
+
+    #[test]
+    fn it_polls_only_woken_tasks() {
+        let arena: &'static Arena<CountPends, 2> =
+            Box::leak(Box::new(Arena::new(CountPends::default())));
+
+        let (a, task_a) = task();
+        let (b, task_b) = task();
+        assert!(arena.spawn(task_a).is_ok());
+        assert!(arena.spawn(task_b).is_ok());
+        assert_eq!(arena.interrupt().count.load(Ordering::SeqCst), 2);
+
+        assert!(arena.poll().is_pending());
+        assert_eq!(a.lock().unwrap().polls, 1);
+        assert_eq!(b.lock().unwrap().polls, 1);
+
+        let waker = b.lock().unwrap().waker.take().unwrap();
+        waker.wake();
+        assert_eq!(arena.interrupt().count.load(Ordering::SeqCst), 3);
+
+        assert!(arena.poll().is_pending());
+        assert_eq!(a.lock().unwrap().polls, 1);
+        assert_eq!(b.lock().unwrap().polls, 2);
+    }
+
+    #[test]
+    fn it_frees_completed_slots() {
+        let arena: &'static Arena<CountPends, 1> =
+            Box::leak(Box::new(Arena::new(CountPends::default())));
+
+        let (a, task_a) = task();
+        let (_, task_b) = task();
+        assert!(arena.spawn(task_a).is_ok());
+        let task_b = arena.spawn(task_b).unwrap_err();
+
+        a.lock().unwrap().is_done = true;
+        assert!(arena.poll().is_ready());
+        assert!(arena.is_empty());
+
+        assert!(arena.spawn(task_b).is_ok());
+        assert_eq!(arena.len(), 1);
+    }
+
+    #[test]
+    fn it_stops_polling_finished_tasks() {
+        let executor: &'static Executor<NonPending, _> =
+            Box::leak(Box::new(Executor::non_pending()));
+
+        let mut polls = 0;
+        executor
+            .spawn(futures::future::poll_fn(move |_| {
+                polls += 1;
+                assert_eq!(polls, 1);
+                Poll::Ready(polls)
+            }))
+            .ok()
+            .unwrap();
+
+        assert!(!executor.is_finished());
+        assert!(executor.poll().is_ready());
+        assert!(executor.poll().is_ready());
+        assert!(executor.is_finished());
+
+        assert_eq!(async_hal::block_on(executor.join(), || {}), 1);
+        assert_eq!(executor.take_output(), None);
+    }
+
+    #[test]
+    fn it_respawns_cancelled_tasks() {
+        let executor: &'static Executor<CountPends, _> =
+            Box::leak(Box::new(Executor::new(CountPends::default())));
+
+        let (a, task_a) = task();
+        let (b, task_b) = task();
+        executor.spawn(task_a).ok().unwrap();
+        assert!(executor.poll().is_pending());
+        assert_eq!(a.lock().unwrap().polls, 1);
+
+        assert!(executor.cancel());
+        assert!(!executor.cancel());
+        assert!(executor.poll().is_pending());
+        assert_eq!(a.lock().unwrap().polls, 1);
+
+        executor.respawn(task_b);
+        assert_eq!(executor.interrupt().count.load(Ordering::SeqCst), 1);
+
+        b.lock().unwrap().is_done = true;
+        assert!(executor.poll().is_ready());
+        assert_eq!(b.lock().unwrap().polls, 1);
+    }
+
+    #[cfg(feature = "mock")]
+    #[test]
+    fn it_cancels_and_respawns_on_an_interrupt() {
+        use async_hal::executor::{MockLine, MockNvic};
+
+        type BoxTask = Pin<Box<dyn Future<Output = ()>>>;
+
+        let nvic = MockNvic::new();
+        let executor: &'static Executor<MockLine, BoxTask> =
+            Box::leak(Box::new(Executor::new(nvic.line("task", 1))));
+        nvic.set_handler(executor.interrupt(), move || _ = executor.poll());
+        let log: &'static Mutex<Vec<&str>> = Box::leak(Box::default());
+
+        executor.respawn(Box::pin(async move {
+            log.lock().unwrap().push("a");
+            futures::future::pending().await
+        }));
+        assert_eq!(*log.lock().unwrap(), ["a"]);
+        assert!(executor.cancel());
+
+        // Respawning from the running task is queued until its poll returns
+        executor.respawn(Box::pin(async move {
+            log.lock().unwrap().push("b");
+            executor.respawn(Box::pin(async move { log.lock().unwrap().push("c") }));
+            futures::future::pending().await
+        }));
+        assert_eq!(*log.lock().unwrap(), ["a", "b", "c"]);
+        assert!(executor.is_finished());
+    }
+
+    #[repr(align(32))]
+    struct Overaligned(u8);
+
+    async_hal::static_task! {
+        async fn overaligned(value: u8, log: &'static Mutex<Vec<u8>>) {
+            let value = Overaligned(value);
+            futures::future::ready(()).await;
+            log.lock().unwrap().push(value.0);
+        }
+    }
+
+    #[test]
+    fn it_stores_static_tasks() {
+        let arena: &'static Arena<CountPends, 1> =
+            Box::leak(Box::new(Arena::new(CountPends::default())));
+        let log: &'static Mutex<Vec<u8>> = Box::leak(Box::default());
+
+        let task = overaligned(7, log).unwrap();
+        assert_eq!(task as *mut _ as usize % 32, 0);
+        assert!(overaligned(8, log).is_none());
+
+        arena.spawn(task).ok().unwrap();
+        assert!(arena.poll().is_ready());
+        assert_eq!(*log.lock().unwrap(), [7]);
+    }
+
+    #[test]
+    fn it_rejects_access_while_polling() {
+        static EXECUTOR: StaticExecutor<Arena<NonPending, 2>> = StaticExecutor::arena(NonPending);
+
+        let (state, task) = task();
+        let inner = Box::leak(Box::new(poll_fn(|_| {
+            // The arena is busy polling this task
+            assert_eq!(EXECUTOR.len(), None);
+            assert!(EXECUTOR.poll().is_pending());
+            Poll::Ready(())
+        })));
+        EXECUTOR.spawn(inner).ok().unwrap();
+        assert!(EXECUTOR.poll().is_ready());
+
+        EXECUTOR.spawn(task).ok().unwrap();
+        assert_eq!(EXECUTOR.len(), Some(1));
+
+        state.lock().unwrap().is_done = true;
+        assert!(EXECUTOR.poll().is_ready());
+        assert_eq!(EXECUTOR.is_empty(), Some(true));
+    }
+
+    #[test]
+    fn it_repolls_after_nested_polls() {
+        static EXECUTOR: StaticExecutor<Arena<NonPending, 2>> = StaticExecutor::arena(NonPending);
+
+        let (state, task) = task();
+        EXECUTOR.spawn(task).ok().unwrap();
+
+        let waker = Box::leak(Box::new(poll_fn(move |_| {
+            // Wake the task in the slot before this one, as if from a nested interrupt
+            state.lock().unwrap().waker.take().unwrap().wake();
+            assert!(EXECUTOR.poll().is_pending());
+            Poll::Ready(())
+        })));
+        EXECUTOR.spawn(waker).ok().unwrap();
+
+        assert!(EXECUTOR.poll().is_pending());
+        assert_eq!(state.lock().unwrap().polls, 2);
+    }
+
+    #[test]
+    fn it_releases_the_executor_when_a_task_panics() {
+        static EXECUTOR: StaticExecutor<Arena<NonPending, 1>> = StaticExecutor::arena(NonPending);
+
+        let task = Box::leak(Box::new(poll_fn(|_| -> Poll<()> { panic!("task failed") })));
+        EXECUTOR.spawn(task).ok().unwrap();
+
+        assert!(std::panic::catch_unwind(|| EXECUTOR.poll()).is_err());
+        assert_eq!(EXECUTOR.len(), Some(0));
+
+        // The slot of the panicked task is free for a new one
+        let task = Box::leak(Box::new(async {}));
+        EXECUTOR.spawn(task).ok().unwrap();
+        assert!(EXECUTOR.poll().is_ready());
+    }
+
+    #[test]
+    fn it_spawns_from_tasks_onto_other_executors() {
+        static LOW: StaticExecutor<Arena<NonPending, 1>> = StaticExecutor::arena(NonPending);
+        static HIGH: StaticExecutor<Arena<CountPends, 1>> = StaticExecutor::arena(CountPends {
+            count: AtomicUsize::new(0),
+        });
+
+        let (state, task) = task();
+        let spawner: Spawner<CountPends, 1> = HIGH.spawner();
+        let parent = Box::leak(Box::new(async move {
+            spawner.spawn(task).ok().unwrap();
+
+            // The only slot is now taken
+            let (_, extra) = self::task();
+            assert!(spawner.spawn(extra).is_err());
+        }));
+        LOW.spawn(parent).ok().unwrap();
+        assert!(LOW.poll().is_ready());
+        assert_eq!(HIGH.len(), Some(1));
+
+        state.lock().unwrap().is_done = true;
+        assert!(HIGH.poll().is_ready());
+        assert_eq!(state.lock().unwrap().polls, 1);
+    }
+
+    #[test]
+    fn it_runs_borrowed_tasks_to_completion() {
+        static PENDS: CountPends = CountPends {
+            count: AtomicUsize::new(0),
+        };
+
+        let ready = Cell::new(false);
+        let mut received = None;
+
+        let mut executor = ScopedExecutor::<_, 2>::new(&PENDS);
+        let waker = RefCell::new(None);
+        let consumer = pin!(poll_fn(|cx| {
+            if ready.get() {
+                received = Some(42);
+                Poll::Ready(())
+            } else {
+                *waker.borrow_mut() = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }));
+        executor.spawn(consumer).ok().unwrap();
+        assert!(executor.poll().is_pending());
+
+        // Wakers from `poll` pend the interrupt
+        waker.borrow_mut().take().unwrap().wake();
+        assert_eq!(PENDS.count.load(Ordering::SeqCst), 1);
+
+        // Wakers from `run` are counted by the call instead
+        let producer = pin!(async {
+            ready.set(true);
+            waker.borrow_mut().take().unwrap().wake();
+        });
+        executor.spawn(producer).ok().unwrap();
+
+        let mut waits = 0;
+        executor.run(|| waits += 1);
+
+        assert_eq!(received, Some(42));
+        assert_eq!(waits, 0);
+        assert_eq!(PENDS.count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn it_repolls_when_woken() {
+        static EXECUTOR: StaticExecutor<Arena<EventFlag, 1>> =
+            StaticExecutor::arena(EventFlag::new());
+
+        // Wake the task from another thread, like an ISR, and sleep on the flag until then
+        let (state, task) = task();
+        EXECUTOR.spawn(task).ok().unwrap();
+        assert!(EXECUTOR.interrupt().take());
+        assert!(EXECUTOR.poll().is_pending());
+
+        let isr = std::thread::spawn(move || {
+            let waker = state.lock().unwrap().waker.take().unwrap();
+            state.lock().unwrap().is_done = true;
+            waker.wake();
+        });
+        EXECUTOR.interrupt().wait(std::thread::yield_now);
+        assert!(EXECUTOR.poll().is_ready());
+        isr.join().unwrap();
+        assert_eq!(state.lock().unwrap().polls, 2);
+
+        // Closures and counters see every pend
+        static PENDS: PendCounter = PendCounter::new();
+        let interrupt = executor::pend_fn(|| PENDS.pend());
+        interrupt.pend();
+        executor::waker(&PENDS).wake();
+        assert_eq!(PENDS.take(), 2);
+        assert_eq!(PENDS.count(), 0);
+    }
+}
@@ -0,0 +1,149 @@
+#[cfg(feature = "delay")]
+mod tests {
+    use async_hal::delay::{
+        DelayMs, Duration, Instant, Interval, MissedTickBehavior, Monotonic, Periodic,
+    };
+    use core::{
+        cell::Cell,
+        convert::Infallible,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+    use futures::StreamExt;
+    use std::rc::Rc;
+
+    /// Periodic timer on a shared clock that overruns like hardware,
+    /// expiring once no matter how many periods passed since it was last polled.
+    struct MockPeriodic {
+        now: Rc<Cell<u64>>,
+        period: u64,
+        expiry: Option<u64>,
+    }
+
+    impl DelayMs for MockPeriodic {
+        type Delay = u32;
+        type Error = Infallible;
+
+        fn start(&mut self, ms: Self::Delay) -> Result<(), Self::Error> {
+            self.period = ms.into();
+            self.expiry = Some(self.now.get() + self.period);
+            Ok(())
+        }
+
+        fn poll_delay_ms(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context,
+        ) -> Poll<Result<(), Self::Error>> {
+            let now = self.now.get();
+            match self.expiry {
+                Some(expiry) if now >= expiry => {
+                    // Skip to the first period boundary after now
+                    let periods = (now - expiry) / self.period + 1;
+                    self.expiry = Some(expiry + periods * self.period);
+                    Poll::Ready(Ok(()))
+                }
+                _ => Poll::Pending,
+            }
+        }
+
+        fn cancel(&mut self) -> Result<(), Self::Error> {
+            self.expiry = None;
+            Ok(())
+        }
+    }
+
+    impl Periodic for MockPeriodic {}
+
+    /// Millisecond clock shared with the timer.
+    struct MockMillis(Rc<Cell<u64>>);
+
+    impl Monotonic for MockMillis {
+        type Instant = Instant<1_000>;
+        type Duration = Duration<1_000>;
+
+        fn now(&self) -> Self::Instant {
+            Instant::from_ticks(self.0.get())
+        }
+    }
+
+    /// Create an interval every 10ms on a shared clock.
+    fn interval() -> (Rc<Cell<u64>>, Interval<MockPeriodic, MockMillis, 1_000>) {
+        let now = Rc::new(Cell::new(0));
+        let timer = MockPeriodic {
+            now: now.clone(),
+            period: 0,
+            expiry: None,
+        };
+        let clock = MockMillis(now.clone());
+        (now, Interval::new(timer, clock, Duration::millis(10)))
+    }
+
+    /// Collect the times of `n` ticks every 10ms, where the consumer is busy for 25ms after the second tick.
+    fn ticks(behavior: MissedTickBehavior, n: usize) -> Vec<u64> {
+        let (now, mut interval) = interval();
+        interval.set_missed_tick_behavior(behavior);
+
+        let task = async {
+            let mut times = Vec::new();
+            for i in 0..n {
+                interval.next().await.unwrap().unwrap();
+                times.push(now.get());
+
+                if i == 1 {
+                    now.set(now.get() + 25);
+                }
+            }
+            times
+        };
+        async_hal::block_on(task, || now.set(now.get() + 1))
+    }
+
+    #[test]
+    #[should_panic(expected = "`period` must be non-zero")]
+    fn it_rejects_zero_periods() {
+        let now = Rc::new(Cell::new(0));
+        let timer = MockPeriodic {
+            now: now.clone(),
+            period: 0,
+            expiry: None,
+        };
+        let _: Interval<_, _, 1_000> = Interval::new(timer, MockMillis(now), Duration::millis(0));
+    }
+
+    #[test]
+    fn it_bursts_missed_ticks() {
+        assert_eq!(ticks(MissedTickBehavior::Burst, 6), [0, 10, 35, 35, 40, 50]);
+    }
+
+    #[test]
+    fn it_delays_after_missed_ticks() {
+        assert_eq!(ticks(MissedTickBehavior::Delay, 5), [0, 10, 35, 45, 55]);
+    }
+
+    #[test]
+    fn it_skips_missed_ticks() {
+        assert_eq!(ticks(MissedTickBehavior::Skip, 5), [0, 10, 35, 40, 50]);
+    }
+
+    #[test]
+    fn it_resets() {
+        let (now, mut interval) = interval();
+
+        let task = async {
+            let mut times = Vec::new();
+            for i in 0..4 {
+                interval.next().await.unwrap().unwrap();
+                times.push(now.get());
+
+                // Restart the schedule partway through a period
+                if i == 1 {
+                    now.set(now.get() + 3);
+                    interval.reset().unwrap();
+                }
+            }
+            times
+        };
+        let times = async_hal::block_on(task, || now.set(now.get() + 1));
+        assert_eq!(times, [0, 10, 23, 33]);
+    }
+}
@@ -0,0 +1,46 @@
+#[cfg(feature = "macros")]
+mod tests {
+    use async_hal::executor::SpawnError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Device crate with the interrupt enum expected by the macros.
+    mod pac {
+        #[allow(non_camel_case_types)]
+        #[derive(Clone, Copy)]
+        #[repr(u16)]
+        pub enum Interrupt {
+            TEST_USART3 = 39,
+        }
+
+        unsafe impl cortex_m::interrupt::InterruptNumber for Interrupt {
+            fn number(self) -> u16 {
+                self as u16
+            }
+        }
+    }
+
+    static ECHOED: AtomicUsize = AtomicUsize::new(0);
+
+    /// Echo serial data.
+    #[async_hal::task(interrupt = pac::Interrupt::TEST_USART3)]
+    async fn echo(count: usize, buf: &'static mut [u8; 4]) {
+        buf[0] = 1;
+        ECHOED.fetch_add(count, Ordering::SeqCst);
+        async_hal::yield_now().await;
+    }
+
+    // The handler is exported under its vector name, like the vector table would see it
+    extern "C" {
+        fn TEST_USART3();
+    }
+
+    #[test]
+    fn it_expands_tasks_into_spawn_functions() {
+        // Spawning unmasks the interrupt in the NVIC, which only exists on the device
+        let _spawn: fn(usize, &'static mut [u8; 4]) -> Result<(), SpawnError> = echo;
+
+        // The handler polls the task's executor, which has nothing to run until it's spawned
+        unsafe { TEST_USART3() };
+        assert_eq!(ECHOED.load(Ordering::SeqCst), 0);
+    }
+}
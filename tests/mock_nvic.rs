@@ -0,0 +1,135 @@
+#[cfg(all(feature = "executor", feature = "mock"))]
+mod tests {
+    use async_hal::{
+        executor::{Arena, Interrupt as _, MockNvic, Pend},
+        Interrupt as _,
+    };
+    use core::{cell::RefCell, future::poll_fn, task::Poll};
+    use futures::StreamExt;
+    use std::{sync::Mutex, task::Waker, thread};
+
+    fn log() -> &'static RefCell<Vec<&'static str>> {
+        Box::leak(Box::default())
+    }
+
+    #[test]
+    fn it_preempts_lower_priorities() {
+        let nvic = MockNvic::new();
+        let low = nvic.line("low", 1);
+        let also_low = nvic.line("also low", 1);
+        let high = nvic.line("high", 2);
+        let log = log();
+
+        let (a, b) = (also_low.clone(), high.clone());
+        nvic.set_handler(&low, move || {
+            log.borrow_mut().push("low start");
+            a.pend();
+            b.pend();
+            log.borrow_mut().push("low end");
+        });
+        nvic.set_handler(&also_low, move || log.borrow_mut().push("also low"));
+        nvic.set_handler(&high, move || {
+            assert_eq!(nvic.active(), Some("high"));
+            log.borrow_mut().push("high");
+        });
+
+        low.pend();
+        assert_eq!(*log.borrow(), ["low start", "high", "low end", "also low"]);
+        assert_eq!(nvic.active(), None);
+        assert_eq!(
+            nvic.take_pends(),
+            [
+                Pend {
+                    line: "low",
+                    from: None
+                },
+                Pend {
+                    line: "also low",
+                    from: Some("low")
+                },
+                Pend {
+                    line: "high",
+                    from: Some("low")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_holds_masked_lines() {
+        let nvic = MockNvic::new();
+        let line = nvic.line("line", 1);
+        let log = log();
+        nvic.set_handler(&line, move || log.borrow_mut().push("fired"));
+
+        line.mask();
+        line.pend();
+        assert!(line.is_pending());
+        assert!(log.borrow().is_empty());
+
+        line.unmask();
+        assert!(!line.is_pending());
+        assert_eq!(*log.borrow(), ["fired"]);
+    }
+
+    #[test]
+    fn it_polls_executors_when_woken() {
+        let nvic = MockNvic::new();
+        let dma = nvic.line("dma", 2);
+        let arena: &'static Arena<_, 1> = Box::leak(Box::new(Arena::new(nvic.line("uart", 1))));
+        nvic.set_handler(arena.interrupt(), move || _ = arena.poll());
+
+        // Wait for the DMA interrupt to fire twice
+        let mut dma_irqs = dma.clone();
+        arena
+            .spawn(Box::leak(Box::new(async move {
+                let mut irqs = dma_irqs.interrupts();
+                irqs.next().await.unwrap().unwrap();
+                irqs.next().await.unwrap().unwrap();
+            })))
+            .ok()
+            .unwrap();
+        assert_eq!(arena.len(), 1);
+
+        dma.pend();
+        assert_eq!(arena.len(), 1);
+        dma.pend();
+        assert!(arena.is_empty());
+
+        let uart = |from| Pend { line: "uart", from };
+        let dma = Pend {
+            line: "dma",
+            from: None,
+        };
+        assert_eq!(
+            nvic.take_pends(),
+            [uart(None), dma, uart(Some("dma")), dma, uart(Some("dma"))]
+        );
+    }
+
+    #[test]
+    fn it_defers_wakes_from_other_threads() {
+        let nvic = MockNvic::new();
+        let arena: &'static Arena<_, 1> = Box::leak(Box::new(Arena::new(nvic.line("uart", 1))));
+        nvic.set_handler(arena.interrupt(), move || _ = arena.poll());
+
+        let waker: &'static Mutex<Option<Waker>> = Box::leak(Box::default());
+        let task = Box::leak(Box::new(poll_fn(move |cx| {
+            let mut waker = waker.lock().unwrap();
+            if waker.is_some() {
+                return Poll::Ready(());
+            }
+            *waker = Some(cx.waker().clone());
+            Poll::Pending
+        })));
+        arena.spawn(task).ok().unwrap();
+
+        let woken = waker.lock().unwrap().clone().unwrap();
+        thread::spawn(move || woken.wake()).join().unwrap();
+        assert!(arena.interrupt().is_pending());
+        assert_eq!(arena.len(), 1);
+
+        nvic.dispatch();
+        assert!(arena.is_empty());
+    }
+}
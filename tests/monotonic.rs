@@ -0,0 +1,166 @@
+#[cfg(all(feature = "delay", feature = "mock"))]
+mod tests {
+    use async_hal::delay::{Duration, FreeRunning, Instant, MockClock, Monotonic};
+    use core::{cell::Cell, pin::pin, task::Context};
+    use futures::{task::noop_waker_ref, Future};
+
+    #[test]
+    fn it_delays_until_instants() {
+        let clock = MockClock::<1_000>::new();
+        let task = {
+            let clock = clock.clone();
+            async move {
+                let mut wakes = Vec::new();
+                let mut next = clock.now();
+                for _ in 0..3 {
+                    next += Duration::millis(10);
+                    clock.delay_until(next).await;
+                    wakes.push(clock.now());
+
+                    // Work for longer than a tick
+                    clock.advance(Duration::millis(4));
+                }
+                wakes
+            }
+        };
+
+        let wakes = async_hal::block_on(task, || clock.advance(Duration::millis(1)));
+        assert_eq!(wakes, [10, 20, 30].map(Instant::from_ticks));
+
+        // Relative delays start when they're created
+        let delay = clock.delay(Duration::millis(5));
+        assert_eq!(delay.instant(), Instant::from_ticks(39));
+
+        let mut delay = pin!(delay);
+        let mut cx = Context::from_waker(noop_waker_ref());
+        assert!(delay.as_mut().poll(&mut cx).is_pending());
+        clock.set(Instant::from_ticks(39));
+        assert!(delay.poll(&mut cx).is_ready());
+    }
+
+    #[test]
+    fn it_busy_polls_clocks_without_a_waker() {
+        // The counter only advances when it's read, so nothing else wakes the task
+        let ticks = Cell::new(0);
+        let clock = FreeRunning::<_, 1_000>::new(|| {
+            ticks.set(ticks.get() + 1);
+            ticks.get()
+        });
+
+        let deadline = clock.now() + Duration::millis(10);
+        async_hal::block_on(clock.delay_until(deadline), || {
+            panic!("delay didn't wake its task")
+        });
+        assert!(clock.now() > deadline);
+    }
+
+    #[test]
+    fn it_extends_wrapping_counters() {
+        let ticks = Cell::new(u32::MAX - 1);
+        let clock = FreeRunning::<_, 1_000_000>::new(|| ticks.get());
+        assert_eq!(clock.now(), Instant::from_ticks(0));
+
+        ticks.set(3);
+        assert_eq!(clock.now(), Instant::from_ticks(5));
+
+        // Reads less than a wrap apart are counted in full
+        ticks.set(2);
+        assert_eq!(clock.now(), Instant::from_ticks(5 + u64::from(u32::MAX)));
+    }
+
+    #[cfg(feature = "executor")]
+    #[test]
+    fn it_reaches_deadlines_under_an_executor() {
+        use async_hal::{
+            delay,
+            executor::{Executor, MockLine, MockNvic},
+        };
+        use core::pin::Pin;
+        use std::sync::{
+            atomic::{AtomicU32, Ordering},
+            Mutex,
+        };
+
+        type BoxTask = Pin<Box<dyn Future<Output = ()>>>;
+
+        let nvic = MockNvic::new();
+        let executor: &'static Executor<MockLine, BoxTask> =
+            Box::leak(Box::new(Executor::new(nvic.line("task", 1))));
+        nvic.set_handler(executor.interrupt(), move || _ = executor.poll());
+        let log: &'static Mutex<Vec<Result<(), delay::Elapsed>>> = Box::leak(Box::default());
+
+        // Only advancing the clock wakes the task
+        let clock = MockClock::<1_000>::new();
+        let deadline = clock.now() + Duration::millis(10);
+        executor.respawn({
+            let clock = clock.clone();
+            Box::pin(async move {
+                let output =
+                    delay::with_deadline(&clock, deadline, futures::future::pending()).await;
+                log.lock().unwrap().push(output);
+            })
+        });
+        for _ in 0..10 {
+            assert!(log.lock().unwrap().is_empty());
+            clock.advance(Duration::millis(1));
+        }
+        assert!(executor.is_finished());
+        assert!(log.lock().unwrap().pop().unwrap().is_err());
+
+        // Clocks without a waker are busy-polled by the executor until the deadline
+        static TICKS: AtomicU32 = AtomicU32::new(0);
+        let clock: &'static FreeRunning<_, 1_000> = Box::leak(Box::new(FreeRunning::new(|| {
+            TICKS.fetch_add(1, Ordering::SeqCst)
+        })));
+        let deadline = clock.now() + Duration::millis(10);
+        executor.respawn(Box::pin(async move {
+            let output = delay::with_deadline(clock, deadline, futures::future::pending()).await;
+            log.lock().unwrap().push(output);
+        }));
+        assert!(executor.is_finished());
+        assert!(log.lock().unwrap().pop().unwrap().is_err());
+        assert!(clock.now() > deadline);
+    }
+
+    #[cfg(feature = "nb")]
+    #[test]
+    fn it_counts_timer_periods() {
+        use async_hal::delay::Ticker;
+        use embedded_hal::timer::{CountDown, Periodic};
+        use void::Void;
+
+        /// Periodic timer whose period expires on every other check.
+        struct MockPeriodic {
+            was_checked: bool,
+        }
+
+        impl CountDown for MockPeriodic {
+            type Time = u32;
+
+            fn start<T: Into<u32>>(&mut self, _count: T) {
+                self.was_checked = false;
+            }
+
+            fn wait(&mut self) -> nb::Result<(), Void> {
+                self.was_checked = !self.was_checked;
+                if self.was_checked {
+                    Err(nb::Error::WouldBlock)
+                } else {
+                    Ok(())
+                }
+            }
+        }
+
+        impl Periodic for MockPeriodic {}
+
+        let clock = Ticker::<_, 1_000>::new(MockPeriodic { was_checked: false }, 1u32);
+        assert_eq!(clock.now().ticks(), 0);
+        assert_eq!(clock.now().ticks(), 1);
+
+        // Without a waker the delay busy-polls the clock until enough periods pass
+        let mut waits = 0;
+        async_hal::block_on(clock.delay_until(Instant::from_ticks(3)), || waits += 1);
+        assert_eq!(waits, 0);
+        assert_eq!(clock.now().ticks(), 4);
+    }
+}
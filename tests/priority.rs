@@ -0,0 +1,127 @@
+#[cfg(all(feature = "executor", feature = "mock"))]
+mod tests {
+    use async_hal::executor::{Arena, Level, MockLine, MockNvic};
+    use core::{
+        cell::{Cell, RefCell},
+        future::poll_fn,
+        task::{Poll, Waker},
+    };
+
+    #[derive(Default)]
+    struct Signal {
+        is_set: Cell<bool>,
+        waker: RefCell<Option<Waker>>,
+    }
+
+    impl Signal {
+        fn set(&self) {
+            self.is_set.set(true);
+            if let Some(waker) = self.waker.take() {
+                waker.wake();
+            }
+        }
+
+        async fn wait(&self) {
+            poll_fn(|cx| {
+                if self.is_set.get() {
+                    Poll::Ready(())
+                } else {
+                    *self.waker.borrow_mut() = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            })
+            .await
+        }
+    }
+
+    struct App {
+        low: &'static Arena<Level<MockLine>, 1>,
+        high: &'static Arena<Level<MockLine>, 1>,
+        log: &'static RefCell<Vec<&'static str>>,
+        signal: &'static Signal,
+    }
+
+    fn app() -> App {
+        // Lines start at the same priority, so only `Level::configure` lets one preempt the other
+        let nvic = MockNvic::new();
+        let low: &'static Arena<_, 1> =
+            Box::leak(Box::new(Arena::new(Level::new(nvic.line("low", 0), 1))));
+        let high: &'static Arena<_, 1> =
+            Box::leak(Box::new(Arena::new(Level::new(nvic.line("high", 0), 2))));
+        nvic.set_handler(low.interrupt().get_ref(), move || _ = low.poll());
+        nvic.set_handler(high.interrupt().get_ref(), move || _ = high.poll());
+
+        unsafe {
+            low.interrupt().configure();
+            high.interrupt().configure();
+        }
+        assert_eq!(low.interrupt().get_ref().priority(), 1);
+        assert_eq!(high.interrupt().get_ref().priority(), 2);
+
+        App {
+            low,
+            high,
+            log: Box::leak(Box::default()),
+            signal: Box::leak(Box::default()),
+        }
+    }
+
+    #[test]
+    fn it_preempts_lower_levels() {
+        let App {
+            low,
+            high,
+            log,
+            signal,
+        } = app();
+        assert!(high.interrupt().preempts(low.interrupt()));
+
+        high.spawn(Box::leak(Box::new(async move {
+            signal.wait().await;
+            log.borrow_mut().push("high");
+        })))
+        .ok()
+        .unwrap();
+        assert!(!high.interrupt().get_ref().is_pending());
+
+        low.spawn(Box::leak(Box::new(async move {
+            log.borrow_mut().push("low start");
+            signal.set();
+            log.borrow_mut().push("low end");
+        })))
+        .ok()
+        .unwrap();
+
+        assert_eq!(*log.borrow(), ["low start", "high", "low end"]);
+        assert!(low.is_empty() && high.is_empty());
+    }
+
+    #[test]
+    fn it_defers_lower_levels() {
+        let App {
+            low,
+            high,
+            log,
+            signal,
+        } = app();
+
+        low.spawn(Box::leak(Box::new(async move {
+            signal.wait().await;
+            log.borrow_mut().push("low");
+        })))
+        .ok()
+        .unwrap();
+        assert!(!low.interrupt().get_ref().is_pending());
+
+        high.spawn(Box::leak(Box::new(async move {
+            log.borrow_mut().push("high start");
+            signal.set();
+            log.borrow_mut().push("high end");
+        })))
+        .ok()
+        .unwrap();
+
+        assert_eq!(*log.borrow(), ["high start", "high end", "low"]);
+        assert!(low.is_empty() && high.is_empty());
+    }
+}
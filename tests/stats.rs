@@ -0,0 +1,89 @@
+#[cfg(all(feature = "executor", feature = "stats"))]
+mod tests {
+    use async_hal::{
+        delay::FreeRunning,
+        executor::{Arena, Executor, NonPending},
+        stats::{self, TaskStats},
+    };
+    use core::{
+        future::{poll_fn, Future},
+        sync::atomic::{AtomicU32, Ordering},
+        task::Poll,
+    };
+    use std::fmt::Write;
+
+    static TICKS: AtomicU32 = AtomicU32::new(0);
+
+    static CLOCK: FreeRunning<fn() -> u32, 1_000_000> =
+        FreeRunning::new(|| TICKS.load(Ordering::SeqCst));
+
+    /// Future that spends `ticks[n]` ticks in its `n`th poll and wakes itself until they run out.
+    fn busy(ticks: &'static [u32]) -> impl Future<Output = ()> {
+        let mut polls = 0;
+        poll_fn(move |cx| {
+            TICKS.fetch_add(ticks[polls], Ordering::SeqCst);
+            polls += 1;
+
+            if polls == ticks.len() {
+                Poll::Ready(())
+            } else {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        })
+    }
+
+    #[test]
+    fn it_records_executor_stats() {
+        stats::set_clock(&CLOCK);
+
+        let executor: &'static Executor<_, _> = Box::leak(Box::new(Executor::non_pending()));
+        executor.spawn(busy(&[5, 20, 10])).ok().unwrap();
+        while executor.poll().is_pending() {}
+
+        assert_eq!(
+            executor.stats(),
+            TaskStats {
+                polls: 3,
+                wakes: 2,
+                busy: 35,
+                longest: 20,
+            }
+        );
+
+        // Respawning starts over
+        let _ = executor.take_output();
+        executor.respawn(busy(&[1]));
+        assert_eq!(executor.stats(), TaskStats::default());
+    }
+
+    #[test]
+    fn it_records_arena_stats_per_slot() {
+        let arena: &'static Arena<NonPending, 3> = Box::leak(Box::new(Arena::non_pending()));
+        arena.spawn(Box::leak(Box::new(busy(&[0])))).ok().unwrap();
+        arena
+            .spawn(Box::leak(Box::new(busy(&[0, 0, 0]))))
+            .ok()
+            .unwrap();
+        while arena.poll().is_pending() {}
+
+        let [first, second, empty] = arena.stats();
+        assert_eq!((first.polls, first.wakes), (1, 0));
+        assert_eq!((second.polls, second.wakes), (3, 2));
+        assert_eq!(empty, TaskStats::default());
+
+        let mut dump = String::new();
+        for (slot, stats) in arena.stats().iter().enumerate() {
+            writeln!(dump, "{slot}: {stats}").unwrap();
+        }
+        assert!(dump.starts_with("0: polls: 1, wakes: 0"));
+    }
+
+    #[test]
+    fn it_records_block_on_stats() {
+        async_hal::block_on(busy(&[0, 0]), || {});
+
+        let stats = stats::block_on();
+        assert_eq!((stats.polls, stats.wakes), (2, 1));
+    }
+}
@@ -0,0 +1,66 @@
+#[cfg(feature = "executor")]
+mod tests {
+    use async_hal::{coop, executor::Executor, task_local::LocalKey};
+    use core::{
+        cell::RefCell,
+        future::poll_fn,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+    use futures::Future;
+
+    async_hal::task_local! {
+        static NODE: u8;
+        static TAG: &'static str;
+    }
+
+    async fn node() -> u8 {
+        coop::yield_now().await;
+        NODE.get()
+    }
+
+    fn is_set<T>(key: &'static LocalKey<T>) -> bool {
+        key.try_with(|_| ()).is_ok()
+    }
+
+    // The innermost scope is shared by the whole process, so these run in a single test
+    #[test]
+    fn it_scopes_values_to_tasks() {
+        // Values last across awaits and can be shadowed by nested scopes
+        let task = NODE.scope(1, async {
+            let outer = node().await;
+            let inner = NODE.scope(2, node()).await;
+            let tag = TAG.scope("can", async { (node().await, TAG.get()) }).await;
+            (outer, inner, tag, node().await)
+        });
+        assert_eq!(async_hal::block_on(task, || {}), (1, 2, (1, "can"), 1));
+        assert!(!is_set(&NODE));
+
+        // Tasks on other executors don't see the values of the task that polls them
+        struct Probe;
+
+        impl Future for Probe {
+            type Output = bool;
+
+            fn poll(self: Pin<&mut Self>, _cx: &mut Context) -> Poll<bool> {
+                Poll::Ready(is_set(&NODE))
+            }
+        }
+
+        let executor: &'static Executor<_, Probe> = Box::leak(Box::new(Executor::non_pending()));
+        executor.spawn(Probe).ok().unwrap();
+
+        let seen = RefCell::new(None);
+        let task = NODE.scope(
+            3,
+            poll_fn(|_| {
+                assert!(is_set(&NODE));
+                assert!(executor.poll().is_ready());
+                *seen.borrow_mut() = executor.take_output();
+                Poll::Ready(())
+            }),
+        );
+        async_hal::block_on(task, || {});
+        assert_eq!(*seen.borrow(), Some(false));
+    }
+}
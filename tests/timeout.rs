@@ -0,0 +1,48 @@
+#[cfg(all(feature = "can", feature = "delay", feature = "mock"))]
+mod tests {
+    use async_hal::{
+        can::{CanReceive, MockFrame},
+        delay::{SinkTimeoutExt, TimeoutError},
+        sim::{self, Simulation},
+    };
+    use embedded_hal::can::{Frame, StandardId};
+
+    fn frame(data: &[u8]) -> MockFrame {
+        MockFrame::new(StandardId::ZERO, data).unwrap()
+    }
+
+    #[test]
+    fn it_gives_up_after_the_delay() {
+        let sim = Simulation::new(0);
+        let (tx, mut rx) = sim::channel();
+
+        let injected = tx.clone();
+        sim.at(30, move || injected.send(frame(&[1])));
+
+        let mut delay = sim.delay();
+        let task = {
+            let sim = sim.clone();
+            async move {
+                let first = rx.receive_timeout(&mut delay, 50).await;
+                assert_eq!(sim.now(), 30);
+
+                let second = rx.receive_timeout(&mut delay, 50).await;
+                assert_eq!(sim.now(), 80);
+
+                // Sends that complete in time aren't affected
+                let mut tx = tx;
+                let sent = tx.send_timeout(frame(&[2]), &mut delay, 50).await;
+                let third = rx.receive_timeout(&mut delay, 50).await;
+
+                (first, second, sent, third)
+            }
+        };
+
+        let (first, second, sent, third) = sim.run(task);
+        assert_eq!(first.unwrap().unwrap().unwrap().data, [1]);
+        assert!(matches!(second, Err(TimeoutError::Elapsed(_))));
+        assert_eq!(sent, Ok(Ok(())));
+        assert_eq!(third.unwrap().unwrap().unwrap().data, [2]);
+        assert_eq!(sim.now(), 80);
+    }
+}
@@ -0,0 +1,78 @@
+#[cfg(all(feature = "delay", feature = "nb", feature = "rt"))]
+mod tests {
+    use async_hal::{
+        binding::On,
+        delay::{DelayMs, Timer},
+    };
+    use core::convert::Infallible;
+    use embedded_hal::timer::{Cancel, CountDown};
+    use fugit::MillisDurationU32;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use void::Void;
+
+    static IS_EXPIRED: AtomicBool = AtomicBool::new(false);
+
+    /// Countdown that expires when its interrupt handler runs, standing in for a HAL counter.
+    struct MockCountDown;
+
+    impl CountDown for MockCountDown {
+        type Time = MillisDurationU32;
+
+        fn start<T: Into<Self::Time>>(&mut self, _count: T) {
+            IS_EXPIRED.store(false, Ordering::SeqCst);
+        }
+
+        fn wait(&mut self) -> nb::Result<(), Void> {
+            if IS_EXPIRED.swap(false, Ordering::SeqCst) {
+                Ok(())
+            } else {
+                Err(nb::Error::WouldBlock)
+            }
+        }
+    }
+
+    impl Cancel for MockCountDown {
+        type Error = Infallible;
+
+        fn cancel(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    #[allow(non_camel_case_types)]
+    enum interrupt {
+        TEST_TIM2,
+    }
+
+    #[allow(non_camel_case_types)]
+    enum TEST_TIM2 {}
+
+    // The handler is exported under its vector name, like the vector table would see it
+    extern "C" {
+        fn TEST_TIM2();
+    }
+
+    async_hal::bind_interrupts! {
+        struct Irqs {
+            TEST_TIM2 => On<TEST_TIM2, MockCountDown>;
+        }
+    }
+
+    #[test]
+    fn it_wakes_from_the_counter_interrupt() {
+        // Counters from other crates are wrapped to implement `Driver`
+        let mut timer = Timer::bind(On::new(MockCountDown), Irqs);
+
+        // `block_on` only polls again once the handler wakes the timer
+        let mut waits = 0;
+        async_hal::block_on(timer.delay_ms(10), || {
+            waits += 1;
+            if waits == 2 {
+                IS_EXPIRED.store(true, Ordering::SeqCst);
+                unsafe { TEST_TIM2() };
+            }
+        })
+        .unwrap();
+        assert_eq!(waits, 2);
+    }
+}
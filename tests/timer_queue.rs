@@ -0,0 +1,79 @@
+#[cfg(all(feature = "delay", feature = "mock"))]
+mod tests {
+    use async_hal::delay::{DelayMs, Instant, MockClock, Monotonic, TimerQueue};
+    use core::{cell::RefCell, pin::pin, task::Context};
+    use futures::{task::noop_waker_ref, FutureExt};
+
+    fn alarm(queue: &TimerQueue<MockClock<1_000>, 1_000>) -> Option<u64> {
+        queue.alarm().next_alarm().map(|instant| instant.ticks())
+    }
+
+    #[test]
+    fn it_multiplexes_timers_on_one_alarm() {
+        let queue = TimerQueue::<_, 1_000>::new(MockClock::new());
+        let order = RefCell::new(Vec::new());
+
+        let timers = [30, 10, 20, 10].map(|ms| {
+            let queue = &queue;
+            let order = &order;
+            async move {
+                let mut timer = pin!(queue.timer());
+                timer.delay_ms(ms).await.unwrap();
+                order.borrow_mut().push((ms, queue.alarm().now().ticks()));
+            }
+        });
+
+        let mut alarms = Vec::new();
+        let [a, b, c, d] = timers;
+        async_hal::block_on(futures::future::join4(a, b, c, d), || {
+            alarms.push(alarm(&queue));
+
+            let clock = queue.alarm();
+            clock.set(clock.next_alarm().unwrap());
+            queue.on_interrupt();
+        });
+
+        // Timers expire in order of their deadlines, one alarm per distinct deadline
+        assert_eq!(*order.borrow(), [(10, 10), (10, 10), (20, 20), (30, 30)]);
+        assert_eq!(alarms, [Some(10), Some(20), Some(30)]);
+        assert_eq!(alarm(&queue), None);
+    }
+
+    #[test]
+    fn it_reprograms_the_alarm_on_cancel() {
+        let queue = TimerQueue::<_, 1_000>::new(MockClock::new());
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        let mut a = pin!(queue.timer());
+        let mut b = pin!(queue.timer());
+        {
+            let mut later = a.delay_ms(50);
+            assert!(later.poll_unpin(&mut cx).is_pending());
+            assert_eq!(alarm(&queue), Some(50));
+
+            let mut sooner = b.delay_ms(5);
+            assert!(sooner.poll_unpin(&mut cx).is_pending());
+            assert_eq!(alarm(&queue), Some(5));
+
+            // Dropping the earliest delay cancels it and moves the alarm to the next deadline
+            drop(sooner);
+            assert_eq!(alarm(&queue), Some(50));
+
+            // Deadlines that pass before they're polled complete without waiting for the alarm
+            queue.alarm().set(Instant::from_ticks(60));
+            assert!(later.poll_unpin(&mut cx).is_ready());
+            assert_eq!(alarm(&queue), None);
+        }
+
+        {
+            let mut c = pin!(queue.timer());
+            let mut delay = c.delay_ms(10);
+            assert!(delay.poll_unpin(&mut cx).is_pending());
+            core::mem::forget(delay);
+            assert_eq!(alarm(&queue), Some(70));
+        }
+
+        // Dropping a queued timer unlinks it and disables the alarm
+        assert_eq!(alarm(&queue), None);
+    }
+}
@@ -0,0 +1,132 @@
+//! Waker tests that are also run under Miri:
+//! `MIRIFLAGS=-Zmiri-ignore-leaks cargo +nightly miri test --features full --test waker`
+#[cfg(feature = "executor")]
+mod tests {
+    use async_hal::executor::{self, Arena, Executor, Interrupt};
+    use core::{
+        future::poll_fn,
+        sync::atomic::{AtomicUsize, Ordering},
+        task::{Poll, Waker},
+    };
+    use std::{sync::Mutex, thread};
+
+    struct CountPends {
+        count: &'static AtomicUsize,
+    }
+
+    impl CountPends {
+        fn new() -> Self {
+            Self {
+                count: Box::leak(Box::default()),
+            }
+        }
+
+        fn count(&self) -> usize {
+            self.count.load(Ordering::SeqCst)
+        }
+    }
+
+    impl Interrupt for CountPends {
+        fn pend(&self) {
+            self.count.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Exercise every entry of the waker's vtable, asserting on the number of pends.
+    fn exercise(waker: &Waker, interrupt: &CountPends) {
+        let start = interrupt.count();
+
+        waker.wake_by_ref();
+        assert_eq!(interrupt.count(), start + 1);
+
+        let cloned = waker.clone();
+        let cloned_again = cloned.clone();
+        drop(cloned);
+        assert_eq!(interrupt.count(), start + 1);
+
+        cloned_again.wake();
+        assert_eq!(interrupt.count(), start + 2);
+
+        let sent = waker.clone();
+        thread::spawn(move || sent.wake()).join().unwrap();
+        assert_eq!(interrupt.count(), start + 3);
+    }
+
+    #[test]
+    fn it_wakes_from_interrupt() {
+        let interrupt: &'static CountPends = Box::leak(Box::new(CountPends::new()));
+        exercise(&executor::waker(interrupt), interrupt);
+    }
+
+    #[test]
+    fn it_wakes_executor() {
+        let interrupt = CountPends::new();
+        let count = interrupt.count;
+        let executor: &'static Executor<CountPends, _> =
+            Box::leak(Box::new(Executor::new(interrupt)));
+
+        let stored: &'static Mutex<Option<Waker>> = Box::leak(Box::default());
+        executor
+            .spawn(poll_fn(move |cx| {
+                exercise(cx.waker(), &CountPends { count });
+                *stored.lock().unwrap() = Some(cx.waker().clone());
+                Poll::<()>::Pending
+            }))
+            .ok()
+            .unwrap();
+        assert!(executor.poll().is_pending());
+
+        // Wakers must stay valid after the poll that created them has returned
+        let waker = stored.lock().unwrap().take().unwrap();
+        exercise(&waker, executor.interrupt());
+        drop(waker);
+    }
+
+    #[test]
+    fn it_wakes_arena_tasks() {
+        let interrupt = CountPends::new();
+        let count = interrupt.count;
+        let arena: &'static Arena<CountPends, 2> = Box::leak(Box::new(Arena::new(interrupt)));
+
+        let stored: &'static Mutex<Option<Waker>> = Box::leak(Box::default());
+        let task = Box::leak(Box::new(poll_fn(move |cx| {
+            exercise(cx.waker(), &CountPends { count });
+            *stored.lock().unwrap() = Some(cx.waker().clone());
+            Poll::Pending
+        })));
+        arena.spawn(task).ok().unwrap();
+        assert!(arena.poll().is_pending());
+
+        let waker = stored.lock().unwrap().take().unwrap();
+        exercise(&waker, arena.interrupt());
+        drop(waker);
+
+        // The task was woken, so it's polled (and exercised) again
+        let count = arena.interrupt().count();
+        assert!(arena.poll().is_pending());
+        assert_eq!(arena.interrupt().count(), count + 3);
+    }
+
+    #[test]
+    fn it_wakes_arena_tasks_from_other_threads() {
+        let arena: &'static Arena<CountPends, 1> =
+            Box::leak(Box::new(Arena::new(CountPends::new())));
+
+        let polls: &'static AtomicUsize = Box::leak(Box::default());
+        let task = Box::leak(Box::new(poll_fn(move |cx| {
+            if polls.fetch_add(1, Ordering::SeqCst) == 2 {
+                return Poll::Ready(());
+            }
+
+            // Wake from another thread while this task is still being polled
+            let waker = cx.waker().clone();
+            thread::spawn(move || waker.wake()).join().unwrap();
+            Poll::Pending
+        })));
+        arena.spawn(task).ok().unwrap();
+
+        while arena.poll().is_pending() {}
+        assert_eq!(polls.load(Ordering::SeqCst), 3);
+        assert_eq!(arena.interrupt().count(), 3);
+    }
+}